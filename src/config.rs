@@ -12,6 +12,17 @@ pub struct Config {
     pub rabbitmq_username: String,
     pub rabbitmq_password: String,
     pub dev_mode: bool,
+    pub map_export_dir: Option<String>,
+    pub map_export_interval: u16,
+    pub strategy: String,
+    pub budget_fleet_share: f32,
+    pub budget_upgrade_share: f32,
+    pub budget_restore_share: f32,
+    pub budget_min_reserve: u64,
+    pub role_explorers: usize,
+    pub role_fighters: usize,
+    pub lookahead_rounds: u32,
+    pub lookahead_time_budget_ms: u64,
 }
 
 impl Config {
@@ -36,6 +47,44 @@ impl Config {
                 Ok(val) => val.to_lowercase() == "true",
                 Err(_) => false,
             },
+            map_export_dir: env::var("MAP_EXPORT_DIR").ok(),
+            map_export_interval: env::var("MAP_EXPORT_INTERVAL")
+                .unwrap_or(10.to_string())
+                .parse::<u16>()
+                .unwrap(),
+            strategy: env::var("STRATEGY").unwrap_or("heuristic".to_string()),
+            budget_fleet_share: env::var("BUDGET_FLEET_SHARE")
+                .unwrap_or(0.5.to_string())
+                .parse::<f32>()
+                .unwrap(),
+            budget_upgrade_share: env::var("BUDGET_UPGRADE_SHARE")
+                .unwrap_or(0.35.to_string())
+                .parse::<f32>()
+                .unwrap(),
+            budget_restore_share: env::var("BUDGET_RESTORE_SHARE")
+                .unwrap_or(0.15.to_string())
+                .parse::<f32>()
+                .unwrap(),
+            budget_min_reserve: env::var("BUDGET_MIN_RESERVE")
+                .unwrap_or(50.to_string())
+                .parse::<u64>()
+                .unwrap(),
+            role_explorers: env::var("ROLE_EXPLORERS")
+                .unwrap_or(1.to_string())
+                .parse::<usize>()
+                .unwrap(),
+            role_fighters: env::var("ROLE_FIGHTERS")
+                .unwrap_or(0.to_string())
+                .parse::<usize>()
+                .unwrap(),
+            lookahead_rounds: env::var("LOOKAHEAD_ROUNDS")
+                .unwrap_or(8.to_string())
+                .parse::<u32>()
+                .unwrap(),
+            lookahead_time_budget_ms: env::var("LOOKAHEAD_TIME_BUDGET_MS")
+                .unwrap_or(100.to_string())
+                .parse::<u64>()
+                .unwrap(),
         }
     }
 }
@@ -1,13 +1,22 @@
 use std::{any::Any, sync::Arc};
 
 use async_trait::async_trait;
-use tracing::info;
+use tracing::{error, info};
 
-use crate::{domainprimitives::{command::command::Command, location::direction::Direction, purchasing::{robot_level::RobotLevel, robot_upgrade::RobotUpgrade, robot_upgrade_type::RobotUpgradeType, trade_item_type::TradeItemType}}, planet::domain::planet::PersistentPlanetInfo, rest::game_service_rest_adapter_trait::GameServiceRestAdapterTrait};
+use crate::{domainprimitives::{command::command::Command, location::direction::Direction, purchasing::{robot_restoration_type::RobotRestorationType, trade_item_type::TradeItemType}}, planet::domain::planet::PersistentPlanetInfo, rest::game_service_rest_adapter_trait::GameServiceRestAdapterTrait, robot::domain::forward_model::PlannedStep};
 
 #[async_trait]
 pub trait Action: Send + Sync {
   fn get_weight(&self) -> f32;
+  fn get_travel_target(&self) -> Option<String> {
+    None
+  }
+  fn get_plan(&self) -> Vec<PlannedStep> {
+    Vec::new()
+  }
+  fn get_purchase(&self) -> Option<TradeItemType> {
+    None
+  }
   async fn execute_command(&self, game_service_rest_adapter: Arc<dyn GameServiceRestAdapterTrait>, player_id: String, robot_id: String);
 }
 
@@ -15,14 +24,27 @@ pub struct MovementAction {
   pub weight: f32,
   pub dir: Direction,
   pub current_planet: PersistentPlanetInfo,
+  pub route: Vec<String>,
 }
 
 impl MovementAction {
   pub fn new(weight: f32, dir: Direction, current_planet: PersistentPlanetInfo) -> Self {
+    let route = Vec::new();
     Self {
       dir,
       weight,
       current_planet,
+      route,
+    }
+  }
+
+  // moves one hop per round, the remaining hops are picked up again next round
+  pub fn along_route(weight: f32, current_planet: PersistentPlanetInfo, route: Vec<String>) -> Self {
+    Self {
+      dir: Direction::Here,
+      weight,
+      current_planet,
+      route,
     }
   }
 }
@@ -33,15 +55,24 @@ impl Action for MovementAction {
     return self.weight;
   }
 
+  fn get_travel_target(&self) -> Option<String> {
+    self.route.last().cloned()
+  }
+
   async fn execute_command(&self, game_service_rest_adapter: Arc<dyn GameServiceRestAdapterTrait>, player_id: String, robot_id: String) {
     let mut planet_id = String::new();
     
-    match self.dir {
-      Direction::East => planet_id = self.current_planet.east.clone(),
-      Direction::North => planet_id = self.current_planet.north.clone(),
-      Direction::South => planet_id = self.current_planet.south.clone(),
-      Direction::West => planet_id = self.current_planet.west.clone(),
-      _ => return,
+    if let Some(next_hop) = self.route.first() {
+      planet_id = next_hop.clone();
+    }
+    else {
+      match self.dir {
+        Direction::East => planet_id = self.current_planet.east.clone(),
+        Direction::North => planet_id = self.current_planet.north.clone(),
+        Direction::South => planet_id = self.current_planet.south.clone(),
+        Direction::West => planet_id = self.current_planet.west.clone(),
+        _ => return,
+      }
     }
 
     let command = Command::create_movement_command(player_id, robot_id.clone(), planet_id.clone());
@@ -82,12 +113,23 @@ impl Action for AttackAction {
 
 pub struct RegenerateAction {
   pub weight: f32,
+  pub travel_target: Option<String>,
 }
 
 impl RegenerateAction {
   pub fn new(weight: f32) -> Self {
+    let travel_target = None;
+    Self {
+      weight,
+      travel_target,
+    }
+  }
+
+  // gathers energy for a route, the robot stays committed to where it is heading
+  pub fn before_travel(weight: f32, travel_target: String) -> Self {
     Self {
       weight,
+      travel_target: Some(travel_target),
     }
   }
 } 
@@ -98,6 +140,10 @@ impl Action for RegenerateAction {
       return self.weight;
   }
 
+  fn get_travel_target(&self) -> Option<String> {
+    self.travel_target.clone()
+  }
+
   async fn execute_command(&self, game_service_rest_adapter: Arc<dyn GameServiceRestAdapterTrait>, player_id: String, robot_id: String) {
       let command = Command::create_robot_regenerate_command(player_id, robot_id.clone());
       info!("====> Trying to Regenerate!!!!!!!!!!!");
@@ -172,6 +218,16 @@ impl PurchaseAction {
       item,
     }
   }
+
+  /// The buy command the trading service expects for the item, restores and upgrades are bought for the robot.
+  pub fn create_command(&self, player_id: String, robot_id: String) -> Command {
+    match (self.item.upgrade(), self.item.restoration()) {
+      (Some(upgrade), _) => Command::create_robot_upgrade_command(player_id, robot_id, &upgrade),
+      (_, Some(RobotRestorationType::EnergyRestore)) => Command::create_robot_purchase_energy_restore_command(player_id, robot_id),
+      (_, Some(RobotRestorationType::HealthRestore)) => Command::create_robot_purchase_health_restore_command(player_id, robot_id),
+      _ => Command::create_robot_purchase_command(player_id, 1),
+    }
+  }
 }
 
 #[async_trait]
@@ -180,164 +236,16 @@ impl Action for PurchaseAction {
     return self.weight;
   }
 
-  async fn execute_command(&self, game_service_rest_adapter: Arc<dyn GameServiceRestAdapterTrait>, player_id: String, robot_id: String) {
-    let mut command = None;
+  fn get_purchase(&self) -> Option<TradeItemType> {
+    Some(self.item)
+  }
 
-    match self.item {
-      TradeItemType::EnergyRestore => {
-          command = Some(Command::create_robot_purchase_energy_restore_command(player_id, robot_id));
-      }
-      TradeItemType::HealthRestore => {
-          command = Some(Command::create_robot_purchase_health_restore_command(player_id, robot_id));
-      }
-      TradeItemType::Damage1 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Damage, RobotLevel::LEVEL1);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Damage2 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Damage, RobotLevel::LEVEL2);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Damage3 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Damage, RobotLevel::LEVEL3);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Damage4 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Damage, RobotLevel::LEVEL4);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Damage5 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Damage, RobotLevel::LEVEL5);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Health1 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Health, RobotLevel::LEVEL1);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Health2 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Health, RobotLevel::LEVEL2);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Health3 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Health, RobotLevel::LEVEL3);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Health4 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Health, RobotLevel::LEVEL4);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Health5 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Health, RobotLevel::LEVEL5);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::MiningSpeed1 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::MiningSpeed, RobotLevel::LEVEL1);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::MiningSpeed2 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::MiningSpeed, RobotLevel::LEVEL2);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::MiningSpeed3 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::MiningSpeed, RobotLevel::LEVEL3);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::MiningSpeed4 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::MiningSpeed, RobotLevel::LEVEL4);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::MiningSpeed5 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::MiningSpeed, RobotLevel::LEVEL5);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Mining1 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Mining, RobotLevel::LEVEL1);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Mining2 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Mining, RobotLevel::LEVEL2);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Mining3 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Mining, RobotLevel::LEVEL3);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Mining4 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Mining, RobotLevel::LEVEL4);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Mining5 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Mining, RobotLevel::LEVEL5);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::MaxEnergy1 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::MaxEnergy, RobotLevel::LEVEL1);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::MaxEnergy2 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::MaxEnergy, RobotLevel::LEVEL2);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::MaxEnergy3 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::MaxEnergy, RobotLevel::LEVEL3);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::MaxEnergy4 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::MaxEnergy, RobotLevel::LEVEL4);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::MaxEnergy5 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::MaxEnergy, RobotLevel::LEVEL5);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::EnergyRegen1 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::EnergyRegen, RobotLevel::LEVEL1);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::EnergyRegen2 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::EnergyRegen, RobotLevel::LEVEL2);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::EnergyRegen3 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::EnergyRegen, RobotLevel::LEVEL3);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::EnergyRegen4 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::EnergyRegen, RobotLevel::LEVEL4);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::EnergyRegen5 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::EnergyRegen, RobotLevel::LEVEL5);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Storage1 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Storage, RobotLevel::LEVEL1);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Storage2 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Storage, RobotLevel::LEVEL2);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Storage3 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Storage, RobotLevel::LEVEL3);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Storage4 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Storage, RobotLevel::LEVEL4);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      TradeItemType::Storage5 => {
-          let upgrade = RobotUpgrade::new(RobotUpgradeType::Storage, RobotLevel::LEVEL5);
-          command = Some(Command::create_robot_upgrade_command(player_id, robot_id, &upgrade));
-      }
-      _ => {},
-    }
-    
+  async fn execute_command(&self, game_service_rest_adapter: Arc<dyn GameServiceRestAdapterTrait>, player_id: String, robot_id: String) {
+    let command = self.create_command(player_id, robot_id.clone());
     info!("====> Trying to Purchase Item!!!!!!!!!!!");
-    
-    match command {
-      Some(command) => { let cmd = game_service_rest_adapter.send_command(command).await; println!("{:?}", cmd); },
-      None => {},
+    info!("robot ({}) buys {:?}", robot_id, self.item);
+    if let Err(e) = game_service_rest_adapter.send_command(command).await {
+      error!("Failed to buy {:?} for robot ({}): {}", self.item, robot_id, e);
     }
   }
 }
@@ -367,8 +275,77 @@ impl Action for NoneAction {
   }
 }
 
+pub struct PlannedAction {
+  pub action: Box<dyn Action + Send + Sync>,
+  pub plan: Vec<PlannedStep>,
+}
+
+impl PlannedAction {
+  // the first step of a plan, `plan` are the steps of the next rounds
+  pub fn new(action: Box<dyn Action + Send + Sync>, plan: Vec<PlannedStep>) -> Self {
+    Self {
+      action,
+      plan,
+    }
+  }
+}
+
+#[async_trait]
+impl Action for PlannedAction {
+  fn get_weight(&self) -> f32 {
+    self.action.get_weight()
+  }
+
+  fn get_travel_target(&self) -> Option<String> {
+    self.action.get_travel_target()
+  }
+
+  fn get_plan(&self) -> Vec<PlannedStep> {
+    self.plan.clone()
+  }
+
+  fn get_purchase(&self) -> Option<TradeItemType> {
+    self.action.get_purchase()
+  }
+
+  async fn execute_command(&self, game_service_rest_adapter: Arc<dyn GameServiceRestAdapterTrait>, player_id: String, robot_id: String) {
+    self.action.execute_command(game_service_rest_adapter, player_id, robot_id).await;
+  }
+}
+
 pub async fn execute_purchase_robots_command(game_service_rest_adapter: Arc<dyn GameServiceRestAdapterTrait>, player_id: String, amount: u16) {
   let buy_robot_command = Command::create_robot_purchase_command(player_id, amount);
   info!("====> Try to buy Robots!!!!!!!!!!!!!!.");
   let _ = game_service_rest_adapter.send_command(buy_robot_command).await;
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_purchase_commands_use_the_trading_names() {
+    let items = [
+      (TradeItemType::MiningSpeed1, "MINING_SPEED_1"), (TradeItemType::MiningSpeed5, "MINING_SPEED_5"),
+      (TradeItemType::MaxEnergy1, "MAX_ENERGY_1"), (TradeItemType::MaxEnergy5, "MAX_ENERGY_5"),
+      (TradeItemType::EnergyRegen1, "ENERGY_REGEN_1"), (TradeItemType::EnergyRegen5, "ENERGY_REGEN_5"),
+      (TradeItemType::Storage1, "STORAGE_1"), (TradeItemType::Storage5, "STORAGE_5"),
+      (TradeItemType::Mining1, "MINING_1"), (TradeItemType::Mining5, "MINING_5"),
+      (TradeItemType::Health1, "HEALTH_1"), (TradeItemType::Health5, "HEALTH_5"),
+      (TradeItemType::Damage1, "DAMAGE_1"), (TradeItemType::Damage5, "DAMAGE_5"),
+      (TradeItemType::EnergyRestore, "ENERGY_RESTORE"),
+      (TradeItemType::HealthRestore, "HEALTH_RESTORE"),
+    ];
+
+    for (item, name) in items {
+      let json: serde_json::Value = serde_json::from_str(&PurchaseAction::new(0., item).create_command("p".to_string(), "r".to_string()).as_json_string()).unwrap();
+      assert_eq!(json["type"], "buying");
+      assert_eq!(json["data"]["robotId"], "r");
+      assert_eq!(json["data"]["itemName"], name);
+      assert_eq!(json["data"]["itemQuantity"], 1);
+    }
+
+    let json: serde_json::Value = serde_json::from_str(&PurchaseAction::new(0., TradeItemType::Robot).create_command("p".to_string(), "r".to_string()).as_json_string()).unwrap();
+    assert_eq!(json["data"], serde_json::json!({"itemName": "ROBOT", "itemQuantity": 1}));
+  }
+}
@@ -107,8 +107,7 @@ impl Command {
         player_id: String,
         robot_id: String,
     ) -> Command {
-        let energy_restore_item_name =
-            serde_json::to_string(&RobotRestorationType::EnergyRestore).unwrap();
+        let energy_restore_item_name = RobotRestorationType::EnergyRestore.to_string();
         Command {
             player_id,
             command_type: CommandType::BUYING,
@@ -123,8 +122,7 @@ impl Command {
         player_id: String,
         robot_id: String,
     ) -> Command {
-        let health_restore_item_name =
-            serde_json::to_string(&RobotRestorationType::HealthRestore).unwrap();
+        let health_restore_item_name = RobotRestorationType::HealthRestore.to_string();
         Command {
             player_id,
             command_type: CommandType::BUYING,
@@ -152,6 +150,7 @@ impl Command {
 
 #[cfg(test)]
 mod tests {
+    use crate::domainprimitives::purchasing::robot_level::RobotLevel;
     use crate::domainprimitives::purchasing::robot_upgrade_type::RobotUpgradeType;
 
     use super::*;
@@ -269,7 +268,7 @@ mod tests {
         assert_eq!(command.command_object.robot_id, Some(robot_id));
         assert_eq!(
             command.command_object.item_name,
-            Some(String::from("\"ENERGY_RESTORE\""))
+            Some(String::from("ENERGY_RESTORE"))
         );
         assert_eq!(command.command_object.item_quantity, Some(1));
     }
@@ -287,11 +286,34 @@ mod tests {
         assert_eq!(command.command_object.robot_id, Some(robot_id));
         assert_eq!(
             command.command_object.item_name,
-            Some(String::from("\"HEALTH_RESTORE\""))
+            Some(String::from("HEALTH_RESTORE"))
         );
         assert_eq!(command.command_object.item_quantity, Some(1));
     }
 
+    #[test]
+    fn test_purchase_commands_as_json() {
+        let restore = Command::create_robot_purchase_energy_restore_command(
+            String::from("1234"),
+            String::from("5678"),
+        );
+        assert_eq!(
+            restore.as_json_string(),
+            r#"{"playerId":"1234","type":"buying","data":{"robotId":"5678","itemName":"ENERGY_RESTORE","itemQuantity":1}}"#
+        );
+
+        let upgrade = RobotUpgrade::for_type_and_level(RobotUpgradeType::MaxEnergy, RobotLevel::LEVEL1);
+        let upgrade_command = Command::create_robot_upgrade_command(
+            String::from("1234"),
+            String::from("5678"),
+            &upgrade,
+        );
+        assert_eq!(
+            upgrade_command.as_json_string(),
+            r#"{"playerId":"1234","type":"buying","data":{"robotId":"5678","itemName":"MAX_ENERGY_1","itemQuantity":1}}"#
+        );
+    }
+
     #[test]
     fn test_create_robot_attack_command() {
         let player_id = String::from("1234");
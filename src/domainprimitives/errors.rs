@@ -10,4 +10,8 @@ pub enum DomainPrimitiveError {
     NegativeEnergy(u16, u16),
     #[error("Money was tried to be reduced from {0} to {1}")]
     NegativeMoney(u64, u64),
+    #[error("{0} is not an amount of money")]
+    InvalidMoney(String),
+    #[error("{0} is not a tradable we know")]
+    UnknownTradable(String),
 }
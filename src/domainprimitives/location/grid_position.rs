@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domainprimitives::location::compass_direction_dto::CompassDirection;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct GridPosition {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl GridPosition {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn origin() -> Self {
+        Self { x: 0, y: 0 }
+    }
+
+    pub fn neighbour(&self, direction: &CompassDirection) -> GridPosition {
+        GridPosition {
+            x: self.x + direction.x_offset() as i32,
+            y: self.y + direction.y_offset() as i32,
+        }
+    }
+
+    pub fn translate(&self, x_offset: i32, y_offset: i32) -> GridPosition {
+        GridPosition {
+            x: self.x + x_offset,
+            y: self.y + y_offset,
+        }
+    }
+
+    pub fn manhattan_distance(&self, other: &GridPosition) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_neighbour() {
+        let position = GridPosition::new(2, 3);
+        assert_eq!(position.neighbour(&CompassDirection::NORTH), GridPosition::new(2, 2));
+        assert_eq!(position.neighbour(&CompassDirection::EAST), GridPosition::new(3, 3));
+        assert_eq!(position.neighbour(&CompassDirection::SOUTH), GridPosition::new(2, 4));
+        assert_eq!(position.neighbour(&CompassDirection::WEST), GridPosition::new(1, 3));
+    }
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(
+            GridPosition::origin().translate(-4, 7),
+            GridPosition::new(-4, 7)
+        );
+    }
+
+    #[test]
+    fn test_manhattan_distance() {
+        assert_eq!(
+            GridPosition::new(-1, 2).manhattan_distance(&GridPosition::new(3, -1)),
+            7
+        );
+        assert_eq!(GridPosition::origin().manhattan_distance(&GridPosition::origin()), 0);
+    }
+}
@@ -2,3 +2,4 @@ pub mod compass_direction_dto;
 pub mod mineable_resource;
 pub mod mineable_resource_type;
 pub mod direction;
+pub mod grid_position;
@@ -1,52 +1,331 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul};
+use std::str::FromStr;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 use crate::domainprimitives::errors::DomainPrimitiveError;
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+// hundredths of a unit, as precise as the prices of the trading service
+const SCALE: u64 = 100;
+
+/// An amount of money in fixed point, so that adding up prices never drifts and spending more than there is fails.
+/// Amounts too large to hold saturate at the largest one instead of wrapping around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Money {
-    amount: u64,
+    hundredths: u64,
 }
 
 impl Money {
     pub fn from_amount(amount: u64) -> Money {
-        Money { amount }
+        Money { hundredths: amount.saturating_mul(SCALE) }
     }
 
     pub fn zero() -> Money {
-        Money { amount: 0 }
+        Money { hundredths: 0 }
+    }
+
+    /// Whole units, the fraction is dropped.
+    pub fn amount(&self) -> u64 {
+        self.hundredths / SCALE
+    }
+
+    pub fn to_f32(self) -> f32 {
+        (self.hundredths as f64 / SCALE as f64) as f32
+    }
+
+    /// Rounded to hundredths, negative amounts and NaN are nothing.
+    pub fn from_f32(amount: f32) -> Money {
+        Money {
+            hundredths: (amount.max(0.) as f64 * SCALE as f64).round() as u64,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.hundredths == 0
+    }
+
+    // rounded down, so the shares of an amount never add up to more than the amount
+    pub fn share(&self, ratio: f32) -> Money {
+        Money {
+            hundredths: (self.hundredths as f64 * ratio.clamp(0., 1.) as f64) as u64,
+        }
     }
 
+    /// Nothing is free, so nothing can be bought for a zero price.
     pub fn can_buy_that_many_for(&self, price: &Money) -> u64 {
-        self.amount / price.amount
+        if price.is_zero() {
+            return 0;
+        }
+        self.hundredths / price.hundredths
     }
 
     pub fn is_less_than(&self, other: &Money) -> bool {
-        self.amount < other.amount
+        self.hundredths < other.hundredths
     }
 
     pub fn is_greater_than(&self, other: &Money) -> bool {
-        self.amount > other.amount
+        self.hundredths > other.hundredths
     }
 
     pub fn is_greater_equal_than(&self, other: &Money) -> bool {
-        self.amount >= other.amount
+        self.hundredths >= other.hundredths
     }
 
     pub fn increase_by(self, other: &Money) -> Money {
         Money {
-            amount: self.amount + other.amount,
+            hundredths: self.hundredths.saturating_add(other.hundredths),
         }
     }
 
     pub fn decrease_by(self, other: &Money) -> Result<Money, DomainPrimitiveError> {
         if self.is_less_than(other) {
             return Err(DomainPrimitiveError::NegativeMoney(
-                self.amount,
-                other.amount,
+                self.amount(),
+                other.amount(),
             ));
         }
         Ok(Money {
-            amount: self.amount - other.amount,
+            hundredths: self.hundredths - other.hundredths,
         })
     }
+
+    /// What is left after spending `other`, nothing if it is more than there is.
+    pub fn saturating_sub(self, other: &Money) -> Money {
+        Money {
+            hundredths: self.hundredths.saturating_sub(other.hundredths),
+        }
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        self.increase_by(&other)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        *self = self.increase_by(&other);
+    }
+}
+
+impl Mul<u64> for Money {
+    type Output = Money;
+
+    fn mul(self, quantity: u64) -> Money {
+        Money {
+            hundredths: self.hundredths.saturating_mul(quantity),
+        }
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::zero(), Add::add)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fraction = self.hundredths % SCALE;
+        if fraction == 0 {
+            write!(f, "{}", self.amount())
+        } else {
+            write!(f, "{}.{:02}", self.amount(), fraction)
+        }
+    }
+}
+
+/// Parses a decimal like `12`, `12.5` or `0.125`, digits beyond the hundredths are rounded.
+impl FromStr for Money {
+    type Err = DomainPrimitiveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DomainPrimitiveError::InvalidMoney(s.to_string());
+        let (whole, fraction) = s.trim().split_once('.').unwrap_or((s.trim(), ""));
+        if (whole.is_empty() && fraction.is_empty()) || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let whole: u64 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+        let digits: Vec<u64> = fraction.chars().map(|c| c as u64 - '0' as u64).collect();
+        let hundredths = digits.first().copied().unwrap_or(0) * 10 + digits.get(1).copied().unwrap_or(0);
+        let round_up = digits.get(2).is_some_and(|digit| *digit >= 5);
+
+        whole.checked_mul(SCALE)
+            .and_then(|amount| amount.checked_add(hundredths + round_up as u64))
+            .map(|hundredths| Money { hundredths })
+            .ok_or_else(invalid)
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_f64(self.hundredths as f64 / SCALE as f64)
+    }
+}
+
+// the services send money as JSON numbers, sometimes as strings
+struct MoneyVisitor;
+
+impl Visitor<'_> for MoneyVisitor {
+    type Value = Money;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a non-negative amount of money")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Money, E> {
+        value.checked_mul(SCALE).map(|hundredths| Money { hundredths }).ok_or_else(|| E::custom(format!("Money {} is too large", value)))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Money, E> {
+        u64::try_from(value).map_err(|_| E::custom(format!("Money can't be negative: {}", value))).and_then(|value| self.visit_u64(value))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Money, E> {
+        if !value.is_finite() {
+            return Err(E::custom(format!("Money has to be a finite number: {}", value)));
+        }
+        if value < 0. {
+            return Err(E::custom(format!("Money can't be negative: {}", value)));
+        }
+        // through the decimal representation, so 0.1 stays 0.10, abs turns -0 into 0
+        value.abs().to_string().parse().map_err(E::custom)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Money, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D>(deserializer: D) -> Result<Money, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+/// Money coming in or going out, e.g. a transaction the bank booked or the profit of a robot.
+/// `Money` can't be negative, so the direction is kept next to it. Nothing at all counts as a credit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transfer {
+    Credit(Money),
+    Debit(Money),
+}
+
+impl Transfer {
+    /// What is left of `income` after `spending`.
+    pub fn between(income: Money, spending: Money) -> Transfer {
+        if income.is_greater_equal_than(&spending) {
+            Transfer::Credit(income.saturating_sub(&spending))
+        } else {
+            Transfer::Debit(spending.saturating_sub(&income))
+        }
+    }
+
+    pub fn money(&self) -> Money {
+        match self {
+            Transfer::Credit(money) | Transfer::Debit(money) => *money,
+        }
+    }
+}
+
+impl Ord for Transfer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Transfer::Credit(a), Transfer::Credit(b)) => a.cmp(b),
+            (Transfer::Debit(a), Transfer::Debit(b)) => b.cmp(a),
+            (Transfer::Credit(_), Transfer::Debit(_)) => Ordering::Greater,
+            (Transfer::Debit(_), Transfer::Credit(_)) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for Transfer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Transfer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Transfer::Credit(money) => write!(f, "+{}", money),
+            Transfer::Debit(money) => write!(f, "-{}", money),
+        }
+    }
+}
+
+impl Serialize for Transfer {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let amount = self.money().hundredths as f64 / SCALE as f64;
+        serializer.serialize_f64(if matches!(self, Transfer::Debit(_)) { -amount } else { amount })
+    }
+}
+
+// a signed amount, negative for money going out
+impl<'de> Deserialize<'de> for Transfer {
+    fn deserialize<D>(deserializer: D) -> Result<Transfer, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TransferVisitor;
+
+        impl TransferVisitor {
+            fn transfer(debit: bool, money: Money) -> Transfer {
+                if debit {
+                    Transfer::between(Money::zero(), money)
+                } else {
+                    Transfer::Credit(money)
+                }
+            }
+        }
+
+        impl Visitor<'_> for TransferVisitor {
+            type Value = Transfer;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an amount of money, negative for money going out")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Transfer, E> {
+                MoneyVisitor.visit_u64(value).map(Transfer::Credit)
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Transfer, E> {
+                MoneyVisitor.visit_u64(value.unsigned_abs()).map(|money| Self::transfer(value < 0, money))
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Transfer, E> {
+                MoneyVisitor.visit_f64(value.abs()).map(|money| Self::transfer(value < 0., money))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Transfer, E> {
+                let value = value.trim();
+                match value.strip_prefix('-') {
+                    Some(debit) => MoneyVisitor.visit_str(debit).map(|money| Self::transfer(true, money)),
+                    None => MoneyVisitor.visit_str(value).map(Transfer::Credit),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(TransferVisitor)
+    }
 }
 
 #[cfg(test)]
@@ -100,6 +379,78 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_share_rounds_down() {
+        assert_eq!("99.90".parse::<Money>().unwrap().amount(), 99);
+        assert_eq!(Money::from_amount(99).share(0.5), "49.50".parse().unwrap());
+        assert_eq!(Money::from_amount(99).share(2.), Money::from_amount(99));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let price: Money = "12.25".parse().unwrap();
+        assert_eq!(price + price, "24.5".parse().unwrap());
+        assert_eq!(price * 4, Money::from_amount(49));
+        assert_eq!([price, price, Money::from_amount(1)].into_iter().sum::<Money>(), "25.50".parse().unwrap());
+        assert_eq!(Money::from_amount(10).saturating_sub(&price), Money::zero());
+        assert_eq!(Money::from_amount(10).can_buy_that_many_for(&Money::zero()), 0);
+        assert_eq!(price.to_string(), "12.25");
+        assert_eq!(Money::from_amount(7).to_string(), "7");
+    }
+
+    #[test]
+    fn test_large_amounts_saturate() {
+        let most = Money::from_amount(u64::MAX);
+        assert_eq!(most, Money::from_amount(u64::MAX / SCALE + 1));
+        assert_eq!(Money::from_amount(2) * u64::MAX, most);
+        assert_eq!(most + Money::from_amount(1), most);
+        assert_eq!(Money::from_f32(f32::INFINITY), most);
+    }
+
+    #[test]
+    fn test_from_f32() {
+        assert_eq!(Money::from_f32(7.5), "7.50".parse().unwrap());
+        assert_eq!(Money::from_f32(0.125).to_string(), "0.13");
+        assert_eq!(Money::from_f32(-3.), Money::zero());
+        assert_eq!(Money::from_f32(f32::NAN), Money::zero());
+    }
+
+    #[test]
+    fn test_parse_decimals() {
+        assert_eq!("12".parse::<Money>(), Ok(Money::from_amount(12)));
+        assert_eq!(".5".parse::<Money>().unwrap().to_string(), "0.50");
+        assert_eq!("0.125".parse::<Money>().unwrap().to_string(), "0.13");
+        assert_eq!("-3".parse::<Money>(), Err(DomainPrimitiveError::InvalidMoney("-3".to_string())));
+        assert!("1.2.3".parse::<Money>().is_err());
+        assert!("".parse::<Money>().is_err());
+    }
+
+    #[test]
+    fn test_serde() {
+        assert_eq!(serde_json::from_str::<Money>("150").unwrap(), Money::from_amount(150));
+        assert_eq!(serde_json::from_str::<Money>("0.1").unwrap().to_string(), "0.10");
+        assert_eq!(serde_json::from_str::<Money>(r#""33.33""#).unwrap().to_string(), "33.33");
+        assert!(serde_json::from_str::<Money>("-1").is_err());
+        assert_eq!(serde_json::from_str::<Money>("-0.0").unwrap(), Money::zero());
+        assert!(serde_json::from_str::<Money>("-1.5").unwrap_err().to_string().contains("can't be negative"));
+        assert!(serde_json::from_str::<Money>("1e30").unwrap_err().to_string().contains("is not an amount of money"));
+        assert_eq!(serde_json::to_string(&"2.5".parse::<Money>().unwrap()).unwrap(), "2.5");
+    }
+
+    #[test]
+    fn test_transfers() {
+        assert_eq!(serde_json::from_str::<Transfer>("-12.5").unwrap(), Transfer::Debit("12.50".parse().unwrap()));
+        assert_eq!(serde_json::from_str::<Transfer>("-40").unwrap(), Transfer::Debit(Money::from_amount(40)));
+        assert_eq!(serde_json::from_str::<Transfer>(r#""-0""#).unwrap(), Transfer::Credit(Money::zero()));
+        assert_eq!(serde_json::from_str::<Transfer>("7").unwrap().to_string(), "+7");
+        assert_eq!(serde_json::to_string(&Transfer::Debit(Money::from_amount(3))).unwrap(), "-3.0");
+
+        let loss = Transfer::between(Money::from_amount(10), Money::from_amount(30));
+        assert_eq!(loss, Transfer::Debit(Money::from_amount(20)));
+        assert!(loss < Transfer::Debit(Money::from_amount(5)));
+        assert!(Transfer::Debit(Money::from_amount(5)) < Transfer::between(Money::zero(), Money::zero()));
+    }
+
     #[test]
     fn test_can_buy_that_many_for() {
         assert_eq!(
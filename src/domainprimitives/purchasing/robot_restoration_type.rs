@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum RobotRestorationType {
     HealthRestore,
@@ -8,7 +8,7 @@ pub enum RobotRestorationType {
 }
 
 impl RobotRestorationType {
-    fn to_string(&self) -> String {
+    pub fn to_string(&self) -> String {
         match self {
             RobotRestorationType::HealthRestore => "HEALTH_RESTORE".to_string(),
             RobotRestorationType::EnergyRestore => "ENERGY_RESTORE".to_string(),
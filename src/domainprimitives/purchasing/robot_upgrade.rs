@@ -52,7 +52,7 @@ impl RobotUpgrade {
         };
         let upgrade_type = match self.upgrade_type {
             RobotUpgradeType::Health => "HEALTH",
-            RobotUpgradeType::MaxEnergy => "ENERGY",
+            RobotUpgradeType::MaxEnergy => "MAX_ENERGY",
             RobotUpgradeType::EnergyRegen => "ENERGY_REGEN",
             RobotUpgradeType::Damage => "DAMAGE",
             RobotUpgradeType::MiningSpeed => "MINING_SPEED",
@@ -104,4 +104,11 @@ mod tests {
             RobotUpgrade::for_type_and_level(RobotUpgradeType::Health, RobotLevel::LEVEL5);
         assert_eq!(upgrade.to_string_for_command(), "HEALTH_5");
     }
+
+    #[test]
+    fn test_to_string_for_command_uses_trading_names() {
+        let upgrade =
+            RobotUpgrade::for_type_and_level(RobotUpgradeType::MaxEnergy, RobotLevel::LEVEL2);
+        assert_eq!(upgrade.to_string_for_command(), "MAX_ENERGY_2");
+    }
 }
@@ -1,6 +1,27 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::domainprimitives::errors::DomainPrimitiveError;
+use crate::domainprimitives::location::mineable_resource_type::MineableResourceType;
+
+use super::robot_level::RobotLevel;
+use super::robot_restoration_type::RobotRestorationType;
+use super::robot_upgrade::RobotUpgrade;
 use super::robot_upgrade_type::RobotUpgradeType;
 
-#[derive(Eq, Hash, PartialEq, Clone, Copy)]
+/// What kind of tradable something is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ItemCategory {
+  Upgrade,
+  Restore,
+  Robot,
+  Resource,
+}
+
+/// Everything the trading service sells us, named as the trading service names it.
+#[derive(Debug, Eq, Hash, PartialEq, Clone, Copy)]
 pub enum TradeItemType {
   MiningSpeed1,
   MiningSpeed2,
@@ -43,6 +64,76 @@ pub enum TradeItemType {
 }
 
 impl TradeItemType {
+  pub const ALL: [TradeItemType; 38] = [
+    TradeItemType::MiningSpeed1, TradeItemType::MiningSpeed2, TradeItemType::MiningSpeed3, TradeItemType::MiningSpeed4, TradeItemType::MiningSpeed5,
+    TradeItemType::MaxEnergy1, TradeItemType::MaxEnergy2, TradeItemType::MaxEnergy3, TradeItemType::MaxEnergy4, TradeItemType::MaxEnergy5,
+    TradeItemType::EnergyRegen1, TradeItemType::EnergyRegen2, TradeItemType::EnergyRegen3, TradeItemType::EnergyRegen4, TradeItemType::EnergyRegen5,
+    TradeItemType::Storage1, TradeItemType::Storage2, TradeItemType::Storage3, TradeItemType::Storage4, TradeItemType::Storage5,
+    TradeItemType::Mining1, TradeItemType::Mining2, TradeItemType::Mining3, TradeItemType::Mining4, TradeItemType::Mining5,
+    TradeItemType::Health1, TradeItemType::Health2, TradeItemType::Health3, TradeItemType::Health4, TradeItemType::Health5,
+    TradeItemType::Damage1, TradeItemType::Damage2, TradeItemType::Damage3, TradeItemType::Damage4, TradeItemType::Damage5,
+    TradeItemType::EnergyRestore, TradeItemType::HealthRestore, TradeItemType::Robot,
+  ];
+
+  pub fn category(&self) -> ItemCategory {
+    match self {
+      TradeItemType::EnergyRestore | TradeItemType::HealthRestore => ItemCategory::Restore,
+      TradeItemType::Robot => ItemCategory::Robot,
+      _ => ItemCategory::Upgrade,
+    }
+  }
+
+  /// The upgrade the item buys, None for restores and robots.
+  pub fn upgrade(&self) -> Option<RobotUpgrade> {
+    let (upgrade_type, level) = match self {
+      TradeItemType::MiningSpeed1 => (RobotUpgradeType::MiningSpeed, RobotLevel::LEVEL1),
+      TradeItemType::MiningSpeed2 => (RobotUpgradeType::MiningSpeed, RobotLevel::LEVEL2),
+      TradeItemType::MiningSpeed3 => (RobotUpgradeType::MiningSpeed, RobotLevel::LEVEL3),
+      TradeItemType::MiningSpeed4 => (RobotUpgradeType::MiningSpeed, RobotLevel::LEVEL4),
+      TradeItemType::MiningSpeed5 => (RobotUpgradeType::MiningSpeed, RobotLevel::LEVEL5),
+      TradeItemType::MaxEnergy1 => (RobotUpgradeType::MaxEnergy, RobotLevel::LEVEL1),
+      TradeItemType::MaxEnergy2 => (RobotUpgradeType::MaxEnergy, RobotLevel::LEVEL2),
+      TradeItemType::MaxEnergy3 => (RobotUpgradeType::MaxEnergy, RobotLevel::LEVEL3),
+      TradeItemType::MaxEnergy4 => (RobotUpgradeType::MaxEnergy, RobotLevel::LEVEL4),
+      TradeItemType::MaxEnergy5 => (RobotUpgradeType::MaxEnergy, RobotLevel::LEVEL5),
+      TradeItemType::EnergyRegen1 => (RobotUpgradeType::EnergyRegen, RobotLevel::LEVEL1),
+      TradeItemType::EnergyRegen2 => (RobotUpgradeType::EnergyRegen, RobotLevel::LEVEL2),
+      TradeItemType::EnergyRegen3 => (RobotUpgradeType::EnergyRegen, RobotLevel::LEVEL3),
+      TradeItemType::EnergyRegen4 => (RobotUpgradeType::EnergyRegen, RobotLevel::LEVEL4),
+      TradeItemType::EnergyRegen5 => (RobotUpgradeType::EnergyRegen, RobotLevel::LEVEL5),
+      TradeItemType::Storage1 => (RobotUpgradeType::Storage, RobotLevel::LEVEL1),
+      TradeItemType::Storage2 => (RobotUpgradeType::Storage, RobotLevel::LEVEL2),
+      TradeItemType::Storage3 => (RobotUpgradeType::Storage, RobotLevel::LEVEL3),
+      TradeItemType::Storage4 => (RobotUpgradeType::Storage, RobotLevel::LEVEL4),
+      TradeItemType::Storage5 => (RobotUpgradeType::Storage, RobotLevel::LEVEL5),
+      TradeItemType::Mining1 => (RobotUpgradeType::Mining, RobotLevel::LEVEL1),
+      TradeItemType::Mining2 => (RobotUpgradeType::Mining, RobotLevel::LEVEL2),
+      TradeItemType::Mining3 => (RobotUpgradeType::Mining, RobotLevel::LEVEL3),
+      TradeItemType::Mining4 => (RobotUpgradeType::Mining, RobotLevel::LEVEL4),
+      TradeItemType::Mining5 => (RobotUpgradeType::Mining, RobotLevel::LEVEL5),
+      TradeItemType::Health1 => (RobotUpgradeType::Health, RobotLevel::LEVEL1),
+      TradeItemType::Health2 => (RobotUpgradeType::Health, RobotLevel::LEVEL2),
+      TradeItemType::Health3 => (RobotUpgradeType::Health, RobotLevel::LEVEL3),
+      TradeItemType::Health4 => (RobotUpgradeType::Health, RobotLevel::LEVEL4),
+      TradeItemType::Health5 => (RobotUpgradeType::Health, RobotLevel::LEVEL5),
+      TradeItemType::Damage1 => (RobotUpgradeType::Damage, RobotLevel::LEVEL1),
+      TradeItemType::Damage2 => (RobotUpgradeType::Damage, RobotLevel::LEVEL2),
+      TradeItemType::Damage3 => (RobotUpgradeType::Damage, RobotLevel::LEVEL3),
+      TradeItemType::Damage4 => (RobotUpgradeType::Damage, RobotLevel::LEVEL4),
+      TradeItemType::Damage5 => (RobotUpgradeType::Damage, RobotLevel::LEVEL5),
+      TradeItemType::EnergyRestore | TradeItemType::HealthRestore | TradeItemType::Robot => return None,
+    };
+    Some(RobotUpgrade::new(upgrade_type, level))
+  }
+
+  pub fn restoration(&self) -> Option<RobotRestorationType> {
+    match self {
+      TradeItemType::EnergyRestore => Some(RobotRestorationType::EnergyRestore),
+      TradeItemType::HealthRestore => Some(RobotRestorationType::HealthRestore),
+      _ => None,
+    }
+  }
+
   pub fn get_next_level_item(upgrade_type: RobotUpgradeType, current_level: u16) -> Option<TradeItemType> {
     match upgrade_type {
         RobotUpgradeType::Storage => match current_level {
@@ -103,4 +194,98 @@ impl TradeItemType {
         },
     }
   }
-}
\ No newline at end of file
+}
+
+impl fmt::Display for TradeItemType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match (self.upgrade(), self.restoration()) {
+      (Some(upgrade), _) => write!(f, "{}", upgrade.to_string_for_command()),
+      (_, Some(restoration)) => write!(f, "{}", restoration.to_string()),
+      _ => write!(f, "ROBOT"),
+    }
+  }
+}
+
+impl FromStr for TradeItemType {
+  type Err = DomainPrimitiveError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    TradeItemType::ALL.into_iter().find(|item| item.to_string() == s).ok_or_else(|| DomainPrimitiveError::UnknownTradable(s.to_string()))
+  }
+}
+
+impl Serialize for TradeItemType {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    serializer.serialize_str(&self.to_string())
+  }
+}
+
+impl<'de> Deserialize<'de> for TradeItemType {
+  fn deserialize<D>(deserializer: D) -> Result<TradeItemType, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    let name = String::deserialize(deserializer)?;
+    name.parse().map_err(serde::de::Error::custom)
+  }
+}
+
+/// Anything the trading service has a price for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tradable {
+  Item(TradeItemType),
+  Resource(MineableResourceType),
+}
+
+impl Tradable {
+  pub fn category(&self) -> ItemCategory {
+    match self {
+      Tradable::Item(item) => item.category(),
+      Tradable::Resource(_) => ItemCategory::Resource,
+    }
+  }
+}
+
+impl FromStr for Tradable {
+  type Err = DomainPrimitiveError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let resources = [MineableResourceType::COAL, MineableResourceType::IRON, MineableResourceType::GEM, MineableResourceType::GOLD, MineableResourceType::PLATIN];
+    match resources.into_iter().find(|resource_type| resource_type.to_string() == s) {
+      Some(resource_type) => Ok(Tradable::Resource(resource_type)),
+      None => s.parse().map(Tradable::Item),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_names_round_trip() {
+    for item in TradeItemType::ALL {
+      assert_eq!(item.to_string().parse::<TradeItemType>(), Ok(item));
+      assert_eq!(serde_json::from_str::<TradeItemType>(&serde_json::to_string(&item).unwrap()).unwrap(), item);
+    }
+    assert_eq!(TradeItemType::MaxEnergy2.to_string(), "MAX_ENERGY_2");
+    assert_eq!(TradeItemType::HealthRestore.to_string(), "HEALTH_RESTORE");
+    assert_eq!(serde_json::to_string(&TradeItemType::Robot).unwrap(), r#""ROBOT""#);
+    assert_eq!("MINING_6".parse::<TradeItemType>(), Err(DomainPrimitiveError::UnknownTradable("MINING_6".to_string())));
+  }
+
+  #[test]
+  fn test_categories_and_what_the_items_buy() {
+    assert_eq!(TradeItemType::Storage3.upgrade(), Some(RobotUpgrade::new(RobotUpgradeType::Storage, RobotLevel::LEVEL3)));
+    assert_eq!(TradeItemType::Storage3.category(), ItemCategory::Upgrade);
+    assert_eq!(TradeItemType::EnergyRestore.restoration(), Some(RobotRestorationType::EnergyRestore));
+    assert_eq!(TradeItemType::EnergyRestore.category(), ItemCategory::Restore);
+    assert_eq!(TradeItemType::Robot.upgrade(), None);
+    assert_eq!("GEM".parse::<Tradable>().map(|tradable| tradable.category()), Ok(ItemCategory::Resource));
+    assert_eq!("ROBOT".parse::<Tradable>(), Ok(Tradable::Item(TradeItemType::Robot)));
+    assert!("SHIELD".parse::<Tradable>().is_err());
+  }
+}
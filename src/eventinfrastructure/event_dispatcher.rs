@@ -4,8 +4,8 @@ use tokio::sync::Mutex;
 
 use crate::game::application::game_logic_service::GameLogicService;
 use crate::planet::application::planet_event_handler::{PlanetDiscoveredEventHandler, ResourceMinedEventHandler};
-use crate::robot::application::robot_event_handler::{RobotMovedEventHandler, RobotRegeneratedEventHandler, RobotResourceMinedEventHandler, RobotResourceRemovedEventHandler, RobotUpgradedEventHandler, RobotsRevealedEventHandler};
-use crate::transaction::application::transaction_event_handler::{BankAccountInitializedEventHandler, BankAccountTransactionBookedEventHandler, TradablePricesEventHandler};
+use crate::robot::application::robot_event_handler::{RobotAttackedEventHandler, RobotMovedEventHandler, RobotRegeneratedEventHandler, RobotResourceMinedEventHandler, RobotResourceRemovedEventHandler, RobotUpgradedEventHandler, RobotsRevealedEventHandler};
+use crate::transaction::application::transaction_event_handler::{BankAccountInitializedEventHandler, BankAccountTransactionBookedEventHandler, TradableBoughtEventHandler, TradablePricesEventHandler, TradableSoldEventHandler};
 use crate::{eventinfrastructure::event_handler::EventHandler, robot::application::robot_event_handler::RobotSpawnedEventHandler};
 use crate::eventinfrastructure::game_event::GameEvent;
 use crate::eventinfrastructure::game_event_body_type::GameEventBodyType;
@@ -24,6 +24,7 @@ pub struct EventDispatcher {
     round_status_event_handler: RoundStatusEventHandler,
     robot_spawned_event_handler: RobotSpawnedEventHandler,
     robots_revealed_event_handler: RobotsRevealedEventHandler,
+    robot_attacked_event_handler: RobotAttackedEventHandler,
     resource_mined_event_handler: ResourceMinedEventHandler,
     planet_discovered_handler: PlanetDiscoveredEventHandler,
     robot_resource_mined_handler: RobotResourceMinedEventHandler,
@@ -34,6 +35,8 @@ pub struct EventDispatcher {
     bank_account_init_handler: BankAccountInitializedEventHandler,
     bank_account_transaction_booked_handler: BankAccountTransactionBookedEventHandler,
     tradable_prices_event_handler: TradablePricesEventHandler,
+    tradable_bought_event_handler: TradableBoughtEventHandler,
+    tradable_sold_event_handler: TradableSoldEventHandler,
 }
 
 impl EventDispatcher {
@@ -59,6 +62,7 @@ impl EventDispatcher {
             robots_revealed_event_handler: RobotsRevealedEventHandler::new(
                 game_logic.clone(),
             ),
+            robot_attacked_event_handler: RobotAttackedEventHandler::new(game_logic.clone()),
             resource_mined_event_handler: ResourceMinedEventHandler::new(game_logic.clone()),
             planet_discovered_handler: PlanetDiscoveredEventHandler::new(game_logic.clone()),
             robot_resource_mined_handler: RobotResourceMinedEventHandler::new(game_logic.clone()),
@@ -69,6 +73,8 @@ impl EventDispatcher {
             bank_account_init_handler: BankAccountInitializedEventHandler::new(game_logic.clone()),
             bank_account_transaction_booked_handler: BankAccountTransactionBookedEventHandler::new(game_logic.clone()),
             tradable_prices_event_handler: TradablePricesEventHandler::new(game_logic.clone()),            
+            tradable_bought_event_handler: TradableBoughtEventHandler::new(game_logic.clone()),
+            tradable_sold_event_handler: TradableSoldEventHandler::new(game_logic.clone()),
             // if needed: add Event Handler for remaining Events
         }
     }
@@ -88,6 +94,9 @@ impl EventDispatcher {
             GameEventBodyType::RobotsRevealed(robots_revealed_event) => {
                 self.robots_revealed_event_handler.handle(robots_revealed_event).await;
             }
+            GameEventBodyType::RobotAttacked(robot_attacked_event) => {
+                self.robot_attacked_event_handler.handle(robot_attacked_event).await;
+            }
             GameEventBodyType::ResourceMined(planet_resource_mined_event) => {
                 self.resource_mined_event_handler.handle(planet_resource_mined_event).await;
             }
@@ -118,6 +127,12 @@ impl EventDispatcher {
             GameEventBodyType::TradablePrices(tradable_prices_event) => {
                 self.tradable_prices_event_handler.handle(tradable_prices_event).await;
             }
+            GameEventBodyType::TradableBought(tradable_bought_event) => {
+                self.tradable_bought_event_handler.handle(tradable_bought_event).await;
+            }
+            GameEventBodyType::TradableSold(tradable_sold_event) => {
+                self.tradable_sold_event_handler.handle(tradable_sold_event).await;
+            }
 
             // if needed: Call Event Handler for Remaining Event Types
             _ => {}
@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 
+use crate::domainprimitives::purchasing::money::Money;
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct BankAccountClearedEvent {
     pub player_id: String,
-    pub balance: f32,
+    pub balance: Money,
 }
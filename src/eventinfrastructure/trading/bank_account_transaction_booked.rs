@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
 
+use crate::domainprimitives::purchasing::money::{Money, Transfer};
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct BankAccountTransactionBookedEvent {
     pub player_id: String,
-    pub balance: f32,
-    pub transaction_amount: f32,
+    pub balance: Money,
+    pub transaction_amount: Transfer,
 }
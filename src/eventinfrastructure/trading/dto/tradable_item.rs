@@ -1,12 +1,13 @@
 use serde::{Deserialize, Serialize};
 
+use crate::domainprimitives::purchasing::money::Money;
 use crate::eventinfrastructure::trading::dto::tradable_type::TradableType;
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TradableItem {
     pub name: String,
-    pub price: u16,
+    pub price: Money,
     #[serde(rename = "type")]
     pub tradable_type: TradableType,
 }
@@ -1,7 +1,7 @@
 pub mod bank_account_cleared_event;
 pub mod bank_account_initialized_event;
 pub mod bank_account_transaction_booked;
-mod dto;
+pub mod dto;
 pub mod tradable_bought_event;
 pub mod tradable_prices_event;
 pub mod tradable_sold_event;
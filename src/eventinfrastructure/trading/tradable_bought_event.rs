@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use crate::domainprimitives::purchasing::money::Money;
 use crate::eventinfrastructure::trading::dto::tradable_type::TradableType;
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -11,6 +12,6 @@ pub struct TradableBoughtEvent {
     pub tradable_type: TradableType,
     pub name: String,
     pub amount: u32,
-    pub price_per_unit: f32,
-    pub total_price: f32,
+    pub price_per_unit: Money,
+    pub total_price: Money,
 }
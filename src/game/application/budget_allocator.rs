@@ -0,0 +1,144 @@
+use std::fmt;
+
+use tracing::info;
+
+use crate::config::CONFIG;
+use crate::domainprimitives::purchasing::money::Money;
+use crate::domainprimitives::purchasing::trade_item_type::TradeItemType;
+
+/// An item too expensive for one round's upgrade money, the upgrade money is kept until it is affordable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SavingsTarget {
+  pub item: TradeItemType,
+  pub price: Money,
+}
+
+/// What a strategy may spend on what in one round.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Budget {
+  pub fleet: Money, // new robots
+  pub upgrades: Money, // includes what was saved in earlier rounds
+  pub restores: Money, // energy and health restores
+  pub reserve: Money, // never spent
+  pub savings_target: Option<SavingsTarget>,
+}
+
+impl fmt::Display for Budget {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "fleet {}, upgrades {}, restores {}, reserve {}", self.fleet, self.upgrades, self.restores, self.reserve)?;
+    if let Some(target) = &self.savings_target {
+      write!(f, ", saving for {:?} at {}", target.item, target.price)?;
+    }
+    Ok(())
+  }
+}
+
+/// Sits between the balance updates and the purchases: keeps a minimum reserve and splits the rest between fleet growth,
+/// upgrades and restores by fixed shares. Upgrade money the strategy did not spend because it saves for a target
+/// stays earmarked for upgrades in the next rounds instead of going back into the pot.
+pub struct BudgetAllocator {
+  upgrade_share: f32,
+  restore_share: f32,
+  minimum_reserve: Money,
+  saved: Money,
+  savings_target: Option<SavingsTarget>,
+}
+
+impl BudgetAllocator {
+  /// The shares are relative to each other, they don't have to add up to 1. The fleet gets whatever the others leave.
+  pub fn new(fleet_share: f32, upgrade_share: f32, restore_share: f32, minimum_reserve: Money) -> Self {
+    let total = fleet_share.max(0.) + upgrade_share.max(0.) + restore_share.max(0.);
+    let (upgrade_share, restore_share) = if total > 0. {
+      (upgrade_share.max(0.) / total, restore_share.max(0.) / total)
+    } else {
+      (0., 0.)
+    };
+
+    Self {
+      upgrade_share,
+      restore_share,
+      minimum_reserve,
+      saved: Money::zero(),
+      savings_target: None,
+    }
+  }
+
+  pub fn from_config() -> Self {
+    Self::new(CONFIG.budget_fleet_share, CONFIG.budget_upgrade_share, CONFIG.budget_restore_share, Money::from_amount(CONFIG.budget_min_reserve))
+  }
+
+  pub fn allocate(&self, balance: Money) -> Budget {
+    let reserve = if balance.is_less_than(&self.minimum_reserve) { balance } else { self.minimum_reserve };
+    let available = balance.decrease_by(&reserve).unwrap_or(Money::zero());
+    let saved = if available.is_less_than(&self.saved) { available } else { self.saved };
+    let free = available.decrease_by(&saved).unwrap_or(Money::zero());
+
+    let upgrades = free.share(self.upgrade_share);
+    let restores = free.share(self.restore_share);
+    // rounding leftovers go into the fleet
+    let fleet = free.decrease_by(&upgrades).and_then(|rest| rest.decrease_by(&restores)).unwrap_or(Money::zero());
+
+    Budget {
+      fleet,
+      upgrades: upgrades.increase_by(&saved),
+      restores,
+      reserve,
+      savings_target: self.savings_target,
+    }
+  }
+
+  /// Books what the round spent on upgrades, without a savings target the unspent upgrade money goes back into the pot.
+  pub fn settle(&mut self, budget: &Budget, spent_on_upgrades: Money, savings_target: Option<SavingsTarget>) {
+    self.saved = match savings_target {
+      Some(_) => budget.upgrades.decrease_by(&spent_on_upgrades).unwrap_or(Money::zero()),
+      None => Money::zero(),
+    };
+    if savings_target != self.savings_target {
+      if let Some(target) = &savings_target {
+        info!("Saving for {:?} at {}, {} put aside", target.item, target.price, self.saved);
+      }
+    }
+    self.savings_target = savings_target;
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_keeps_the_reserve_and_splits_the_rest() {
+    let allocator = BudgetAllocator::new(2., 1., 1., Money::from_amount(50));
+
+    let budget = allocator.allocate(Money::from_amount(450));
+    assert_eq!(budget.reserve, Money::from_amount(50));
+    assert_eq!(budget.upgrades, Money::from_amount(100));
+    assert_eq!(budget.restores, Money::from_amount(100));
+    assert_eq!(budget.fleet, Money::from_amount(200));
+    assert_eq!(budget.to_string(), "fleet 200, upgrades 100, restores 100, reserve 50");
+
+    let budget = allocator.allocate(Money::from_amount(30));
+    assert_eq!(budget.reserve, Money::from_amount(30));
+    assert_eq!(budget.fleet, Money::zero());
+  }
+
+  #[test]
+  fn test_saves_for_an_expensive_upgrade_across_rounds() {
+    let mut allocator = BudgetAllocator::new(1., 1., 0., Money::zero());
+    let target = SavingsTarget { item: TradeItemType::Mining3, price: Money::from_amount(300) };
+
+    let budget = allocator.allocate(Money::from_amount(400));
+    assert_eq!(budget.upgrades, Money::from_amount(200));
+    allocator.settle(&budget, Money::zero(), Some(target));
+
+    // of the 400 the saved 200 stay upgrade money, the free 200 are split: upgrades get 300, the fleet 100
+    let budget = allocator.allocate(Money::from_amount(400));
+    assert_eq!(budget.upgrades, Money::from_amount(300));
+    assert_eq!(budget.fleet, Money::from_amount(100));
+    assert_eq!(budget.savings_target, Some(target));
+    allocator.settle(&budget, Money::from_amount(300), None);
+
+    let budget = allocator.allocate(Money::from_amount(400));
+    assert_eq!(budget.upgrades, Money::from_amount(200));
+  }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use tracing::debug;
+
+use crate::game::domain::game_logic_info::{PersistentData, TransientData};
+use crate::robot::domain::robot::TransientRobotInfo;
+
+// rounds of an enemy's mining we count as won back when it is destroyed
+const LOOT_ROUNDS: u32 = 10;
+
+/// The fight on one planet: all our robots there attack the same enemy until it is destroyed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Engagement {
+  pub planet_id: String,
+  pub target_id: String,
+  pub fighters: Vec<String>,
+  pub damage_per_round: u32, // of all fighters together
+  pub kills_per_round: f32, // over the whole fight, destroying one enemy after the other
+}
+
+/// What one robot puts into and gets out of an engagement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FocusFire {
+  pub target_id: String,
+  pub rounds: u32, // until the target is destroyed
+  pub kill_share: f32, // share of the damage the robot deals
+  pub damage_taken: u32, // until the target is destroyed
+  pub loot: f32, // what the target would have mined on the planet
+}
+
+/// Picks the targets of our robots from the health and damage levels on both sides and what the enemies did to us,
+/// several robots on one planet focus their fire on one enemy instead of spreading their damage.
+pub struct CombatPlanner<'a> {
+  round_data: &'a TransientData,
+  game_data: &'a PersistentData,
+}
+
+impl<'a> CombatPlanner<'a> {
+  pub fn new(round_data: &'a TransientData, game_data: &'a PersistentData) -> Self {
+    Self {
+      round_data,
+      game_data,
+    }
+  }
+
+  // what the enemy deals per round, more if it already attacked us
+  fn threat(&self, enemy: &TransientRobotInfo) -> f32 {
+    enemy.damage_level.get_attack_damage_value_for_level() as f32 + self.game_data.combat_log.hostility_of(&enemy.id)
+  }
+
+  fn enemies_on(&self, planet_id: &str) -> Vec<&'a TransientRobotInfo> {
+    self.round_data.enemy_robots.values().filter(|enemy| enemy.planet_id == planet_id && enemy.health > 0).collect()
+  }
+
+  /// One engagement per planet where our robots with more than `min_energy` meet enemies.
+  pub fn engagements(&self, min_energy: u16) -> Vec<Engagement> {
+    let mut fighters_by_planet: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (id, robot) in &self.round_data.robots {
+      if robot.energy > min_energy && self.game_data.robots.contains_key(id) {
+        fighters_by_planet.entry(robot.planet_id.as_str()).or_default().push(id.as_str());
+      }
+    }
+
+    let mut engagements = Vec::new();
+    for (planet_id, mut fighters) in fighters_by_planet {
+      let mut enemies = self.enemies_on(planet_id);
+      if enemies.is_empty() {
+        continue;
+      }
+      fighters.sort();
+      let damage_per_round: u32 = fighters.iter().map(|id| self.game_data.robots[*id].attack_damage as u32).sum::<u32>().max(1);
+
+      // the enemy we destroy fastest first, the more threatening one of two equally fast kills
+      enemies.sort_by(|a, b| {
+        rounds_to_destroy(a.health, damage_per_round).cmp(&rounds_to_destroy(b.health, damage_per_round))
+          .then_with(|| self.threat(b).total_cmp(&self.threat(a)))
+          .then_with(|| a.id.cmp(&b.id))
+      });
+      let total_rounds: u32 = enemies.iter().map(|enemy| rounds_to_destroy(enemy.health, damage_per_round)).sum();
+      let engagement = Engagement {
+        planet_id: planet_id.to_string(),
+        target_id: enemies[0].id.clone(),
+        fighters: fighters.iter().map(|id| id.to_string()).collect(),
+        damage_per_round,
+        kills_per_round: enemies.len() as f32 / total_rounds as f32,
+      };
+      debug!("{} robot(s) focus ({}) on planet ({}), {:.2} kills per round expected", engagement.fighters.len(), engagement.target_id, planet_id, engagement.kills_per_round);
+      engagements.push(engagement);
+    }
+
+    engagements.sort_by(|a, b| a.planet_id.cmp(&b.planet_id));
+    engagements
+  }
+
+  /// What every fighter of the engagement gets out of attacking its target.
+  pub fn focus_fire(&self, engagement: &Engagement) -> HashMap<String, FocusFire> {
+    let Some(target) = self.round_data.enemy_robots.get(&engagement.target_id) else {
+      return HashMap::new();
+    };
+    let rounds = rounds_to_destroy(target.health, engagement.damage_per_round);
+    // every enemy on the planet strikes back, we expect the damage to be spread over our fighters
+    let enemy_damage: u32 = self.enemies_on(&engagement.planet_id).iter().map(|enemy| enemy.damage_level.get_attack_damage_value_for_level() as u32).sum();
+    let damage_per_fighter = enemy_damage.div_ceil(engagement.fighters.len().max(1) as u32);
+    let loot = self.loot(target, &engagement.planet_id);
+
+    engagement.fighters.iter()
+      .filter_map(|id| Some((id, self.game_data.robots.get(id)?, self.round_data.robots.get(id)?)))
+      .map(|(id, robot_info, robot)| {
+        let focus_fire = FocusFire {
+          target_id: target.id.clone(),
+          rounds,
+          kill_share: robot_info.attack_damage as f32 / engagement.damage_per_round as f32,
+          damage_taken: (damage_per_fighter * rounds).min(robot.health as u32),
+          loot,
+        };
+        (id.clone(), focus_fire)
+      })
+      .collect()
+  }
+
+  // the resource the enemy would mine on the planet over the next rounds, ours once it is gone
+  fn loot(&self, enemy: &TransientRobotInfo, planet_id: &str) -> f32 {
+    let Some(resource) = self.game_data.planets.get(planet_id).and_then(|planet| planet.resource) else {
+      return 0.;
+    };
+    if (enemy.mining_level as u8) < (resource.resource_type as u8) {
+      return 0.;
+    }
+    let price = self.round_data.resource_price(resource.resource_type);
+    let amount = (enemy.mining_speed_level.get_mining_speed_value_for_level() as u32 * LOOT_ROUNDS).min(resource.current_amount);
+    amount as f32 * price
+  }
+}
+
+fn rounds_to_destroy(health: u16, damage_per_round: u32) -> u32 {
+  (health as u32).div_ceil(damage_per_round.max(1)).max(1)
+}
+
+#[cfg(test)]
+mod test {
+  use crate::domainprimitives::purchasing::robot_level::RobotLevel;
+  use crate::robot::domain::robot::{Inventory, PersistentRobotInfo};
+
+  use super::*;
+
+  fn data() -> (TransientData, PersistentData) {
+    let mut round_data = TransientData::new();
+    let mut game_data = PersistentData::new();
+    for id in ["a", "b"] {
+      round_data.robots.insert(id.to_string(), TransientRobotInfo::level0(id, "p", 20, 10).with_damage(RobotLevel::LEVEL2));
+      game_data.robots.insert(id.to_string(), PersistentRobotInfo { attack_damage: 5, ..PersistentRobotInfo::fresh(id, Inventory::new(0, 0, 0, 0, 0, false, 0, 20)) });
+    }
+    round_data.enemy_robots.insert("x".to_string(), TransientRobotInfo::level0("x", "p", 20, 25));
+    round_data.enemy_robots.insert("y".to_string(), TransientRobotInfo::level0("y", "p", 20, 10).with_damage(RobotLevel::LEVEL1));
+    round_data.enemy_robots.insert("z".to_string(), TransientRobotInfo::level0("z", "p", 20, 10));
+    (round_data, game_data)
+  }
+
+  #[test]
+  fn test_fighters_focus_the_fastest_and_most_threatening_kill() {
+    let (round_data, game_data) = data();
+    let planner = CombatPlanner::new(&round_data, &game_data);
+
+    let engagements = planner.engagements(3);
+
+    // y and z go down in one round each, y hits harder, x takes 3 rounds
+    assert_eq!(engagements.len(), 1);
+    assert_eq!(engagements[0].target_id, "y");
+    assert_eq!(engagements[0].fighters, vec!["a".to_string(), "b".to_string()]);
+    assert_eq!(engagements[0].kills_per_round, 3. / 5.);
+
+    let focus_fire = planner.focus_fire(&engagements[0]);
+    assert_eq!(focus_fire["a"].target_id, "y");
+    assert_eq!(focus_fire["a"].kill_share, 0.5);
+    // 4 damage from the three enemies spread over our two robots
+    assert_eq!(focus_fire["b"].damage_taken, 2);
+  }
+
+  #[test]
+  fn test_enemies_that_attacked_us_come_first() {
+    let (round_data, mut game_data) = data();
+    game_data.combat_log.record_attack_on_us("z", 2, false);
+    let planner = CombatPlanner::new(&round_data, &game_data);
+
+    assert_eq!(planner.engagements(3)[0].target_id, "z");
+    // without energy nobody fights
+    assert!(planner.engagements(20).is_empty());
+  }
+}
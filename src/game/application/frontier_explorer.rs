@@ -0,0 +1,174 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::domainprimitives::location::grid_position::GridPosition;
+use crate::domainprimitives::location::mineable_resource_type::MineableResourceType;
+use crate::domainprimitives::purchasing::money::Money;
+use crate::planet::domain::planet::PersistentPlanetInfo;
+use crate::planet::domain::planet_graph::{Route, ShortestPaths};
+use crate::planet::domain::world_map::WorldMap;
+
+// what we expect to find on an unknown planet as long as we haven't seen any resources yet, in money
+const UNKNOWN_PLANET_VALUE: f32 = 500.;
+// frontier targets this close to an already claimed one are worth less, so robots fan out
+const SPREAD_RADIUS: u32 = 3;
+
+pub struct ExplorationTarget {
+  pub planet_id: String,
+  pub route: Route,
+  pub value: f32, // expected value of the planet, less if other robots explore close by
+}
+
+/// Picks undiscovered planets that we only know by id as the neighbour of a discovered one.
+/// Targets are claimed by the robots the last auction sent there, so every robot explores in its own direction.
+pub struct FrontierExplorer {
+  unexplored: HashSet<String>,
+  claimed: HashMap<String, String>, // planet id -> the robot heading there
+  claimed_positions: Vec<(String, GridPosition)>,
+  expected_planet_value: f32,
+}
+
+impl FrontierExplorer {
+  pub fn new(planets: &HashMap<String, PersistentPlanetInfo>, resource_prices: &HashMap<MineableResourceType, Money>) -> Self {
+    let mut unexplored = HashSet::new();
+    let mut total_value = 0.;
+
+    for planet in planets.values() {
+      for neighbour_id in [&planet.north, &planet.east, &planet.south, &planet.west] {
+        if !neighbour_id.is_empty() && !planets.contains_key(neighbour_id) {
+          unexplored.insert(neighbour_id.clone());
+        }
+      }
+
+      if let Some(resource) = planet.resource {
+        let price = resource_prices.get(&resource.resource_type).copied().map_or(0., Money::to_f32);
+        if price > 0. && !resource.is_empty() {
+          total_value += price * resource.current_amount as f32;
+        }
+      }
+    }
+
+    // what the resources on a planet are worth, averaged over every planet including the empty ones
+    let expected_planet_value = if total_value > 0. {
+      total_value / planets.len() as f32
+    } else {
+      UNKNOWN_PLANET_VALUE
+    };
+
+    Self {
+      unexplored,
+      claimed: HashMap::new(),
+      claimed_positions: Vec::new(),
+      expected_planet_value,
+    }
+  }
+
+  /// The best target for the robot, the one it is already heading to stays its own.
+  pub fn find_target(&self, robot_id: &str, from_planet_id: &str, paths: &ShortestPaths, world_map: &WorldMap) -> Option<ExplorationTarget> {
+    let mut best_target: Option<ExplorationTarget> = None;
+
+    for planet_id in &self.unexplored {
+      if self.claimed.get(planet_id).is_some_and(|claimed_by| claimed_by != robot_id) {
+        continue;
+      }
+
+      if let Some(route) = paths.route_to(planet_id) {
+        let crowding = match world_map.position_of(planet_id) {
+          Some(position) => self.claimed_positions.iter()
+            .filter(|(claimed_by, claimed)| claimed_by != robot_id && claimed.manhattan_distance(&position) <= SPREAD_RADIUS)
+            .count(),
+          None => 0,
+        };
+        let value = self.expected_planet_value / (1 + crowding) as f32;
+        let weight = value / route.hops.len().max(1) as f32;
+
+        let is_better = match &best_target {
+          Some(best) => {
+            let best_weight = best.value / best.route.hops.len().max(1) as f32;
+            weight > best_weight || (weight == best_weight && *planet_id < best.planet_id)
+          }
+          None => true,
+        };
+        if is_better {
+          best_target = Some(ExplorationTarget {
+            planet_id: planet_id.clone(),
+            route,
+            value,
+          });
+        }
+      }
+    }
+
+    if best_target.is_none() {
+      // every frontier planet is taken, so share the closest one rather than standing around
+      let (planet_id, _) = world_map.nearest_unexplored_cell(from_planet_id)?;
+      let route = paths.route_to(&planet_id)?;
+      let value = self.expected_planet_value / (1 + self.claimed.len()) as f32;
+      best_target = Some(ExplorationTarget {
+        planet_id,
+        route,
+        value,
+      });
+    }
+
+    best_target
+  }
+
+  /// Only frontier planets can be claimed, robots travelling anywhere else don't explore.
+  pub fn claim(&mut self, robot_id: &str, planet_id: &str, world_map: &WorldMap) {
+    if !self.unexplored.contains(planet_id) {
+      return;
+    }
+    self.claimed.insert(planet_id.to_string(), robot_id.to_string());
+    if let Some(position) = world_map.position_of(planet_id) {
+      self.claimed_positions.push((robot_id.to_string(), position));
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::planet::domain::planet_graph::PlanetGraph;
+
+  use super::*;
+
+  // unknown_n is north of the spawn, unknown_e1 and unknown_e2 are east of it
+  fn explored_world() -> (HashMap<String, PersistentPlanetInfo>, WorldMap) {
+    let mut planets = HashMap::new();
+    planets.insert("spawn".to_string(), PersistentPlanetInfo::with_neighbours("spawn", "unknown_n", "e", "", ""));
+    planets.insert("e".to_string(), PersistentPlanetInfo::with_neighbours("e", "unknown_e1", "unknown_e2", "", "spawn"));
+
+    let mut world_map = WorldMap::new();
+    world_map.anchor("spawn");
+    world_map.add_planet(&planets["spawn"]);
+    world_map.add_planet(&planets["e"]);
+    (planets, world_map)
+  }
+
+  #[test]
+  fn test_prefers_closest_frontier_planet() {
+    let (planets, world_map) = explored_world();
+    let explorer = FrontierExplorer::new(&planets, &HashMap::new());
+    let paths = PlanetGraph::new(&planets).shortest_paths_from("spawn");
+
+    let target = explorer.find_target("a", "spawn", &paths, &world_map).unwrap();
+    assert_eq!(target.planet_id, "unknown_n");
+    assert_eq!(target.value, UNKNOWN_PLANET_VALUE);
+  }
+
+  #[test]
+  fn test_claimed_targets_spread_robots() {
+    let (planets, world_map) = explored_world();
+    let mut explorer = FrontierExplorer::new(&planets, &HashMap::new());
+    let paths = PlanetGraph::new(&planets).shortest_paths_from("e");
+
+    let first = explorer.find_target("a", "e", &paths, &world_map).unwrap();
+    explorer.claim("a", &first.planet_id, &world_map);
+    explorer.claim("c", "e", &world_map);
+    let second = explorer.find_target("b", "e", &paths, &world_map).unwrap();
+
+    assert_ne!(first.planet_id, second.planet_id);
+    assert!(second.value < first.value);
+    // the robot that claimed the target keeps it
+    assert_eq!(explorer.find_target("a", "e", &paths, &world_map).unwrap().planet_id, first.planet_id);
+  }
+}
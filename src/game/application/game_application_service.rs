@@ -46,7 +46,11 @@ impl GameApplicationService {
     }
 
     pub async fn end_game(&self, game_id: &str) {
-        self.game_logic.lock().await.clear_game();
+        let mut game_logic = self.game_logic.lock().await;
+        game_logic.export_map("map_final");
+        game_logic.report_ledger();
+        game_logic.clear_game();
+        drop(game_logic);
 
         let game = self.game_repository.get(game_id).await.unwrap();
         match game {
@@ -68,8 +72,10 @@ impl GameApplicationService {
         match game {
             Some(mut game) => {
                 game.start_round();
+                let round_number = game.current_round_number;
+                let max_rounds = game.max_rounds;
                 self.game_repository.save(game).await.unwrap();
-                self.game_logic.lock().await.round_move(self.game_service_rest_adapter.clone()).await;
+                self.game_logic.lock().await.round_move(self.game_service_rest_adapter.clone(), round_number, max_rounds).await;
             }
             None => {
                 error!("Game with id {} not found", game_id)
@@ -1,20 +1,32 @@
 use std::sync::Arc;
 
-use crate::domainprimitives::command::action::{execute_purchase_robots_command, Action, AttackAction, MineAction, MovementAction, NoneAction, PurchaseAction, RegenerateAction, SellAction};
-use crate::domainprimitives::location::direction::Direction;
+use tracing::{debug, info, warn};
+
+use crate::domainprimitives::command::action::execute_purchase_robots_command;
 use crate::domainprimitives::location::mineable_resource_type::MineableResourceType;
+use crate::domainprimitives::purchasing::money::{Money, Transfer};
 use crate::domainprimitives::purchasing::robot_level::RobotLevel;
 use crate::domainprimitives::purchasing::robot_upgrade_type::RobotUpgradeType;
 use crate::domainprimitives::purchasing::trade_item_type::TradeItemType;
 use crate::eventinfrastructure::robot;
-use crate::game::domain::game_logic_info::{GameDecisionInfo, PersistentData, TransientData};
+use crate::game::application::budget_allocator::BudgetAllocator;
+use crate::game::application::map_exporter::{MapExporter, MapSnapshot};
+use crate::game::application::role_assigner::RoleAssigner;
+use crate::game::application::strategy::{self, RoundPlan, Strategy};
+use crate::game::domain::game_logic_info::{PersistentData, TransientData};
 use crate::planet::domain::planet::{PersistentPlanetInfo, TransientPlanetInfo};
 use crate::rest::game_service_rest_adapter_trait::{self, GameServiceRestAdapterTrait};
-use crate::robot::domain::robot::{PersistentRobotInfo, Robot, RobotDecisionInfo, TransientRobotInfo};
+use crate::robot::domain::robot::{PersistentRobotInfo, Robot, TransientRobotInfo};
+use crate::transaction::domain::ledger::{EntryKind, LedgerEntry};
+use crate::transaction::domain::reconciliation::{Discrepancy, ExpectedPurchase};
 
 pub struct GameLogicService {
   pub round_data: TransientData,
   pub game_data: PersistentData,
+  strategy: Box<dyn Strategy>,
+  budget_allocator: BudgetAllocator,
+  role_assigner: RoleAssigner,
+  map_exporter: Option<MapExporter>,
 }
 
 impl GameLogicService {
@@ -25,45 +37,70 @@ impl GameLogicService {
     Self {
       round_data: transient_data,
       game_data: persistent_data,
+      strategy: strategy::from_config(),
+      budget_allocator: BudgetAllocator::from_config(),
+      role_assigner: RoleAssigner::from_config(),
+      map_exporter: MapExporter::from_config(),
     }
   }
 
-  pub async fn round_move(&mut self, game_service_rest_adapter: Arc<dyn GameServiceRestAdapterTrait>) {
-
-    self.game_data.robot_buy_amount = 0;
-
-    let mut decision_info = GameDecisionInfo::new();
+  pub async fn round_move(&mut self, game_service_rest_adapter: Arc<dyn GameServiceRestAdapterTrait>, round_number: u16, max_rounds: u16) {
+    self.game_data.current_round = round_number;
+    self.game_data.max_rounds = max_rounds;
 
-    for (id, robot) in &mut self.game_data.robots {
-      let r = RobotDecisionInfo::new(id.clone(), Box::new(NoneAction::new()), Box::new(NoneAction::new()), false);
-      decision_info.robots.insert(id.clone(), r);
+    if self.map_exporter.as_ref().is_some_and(|exporter| exporter.is_due(round_number)) {
+      self.export_map(&format!("map_round_{}", round_number));
     }
 
-    let ids: Vec<String> = self.game_data.robots.keys().cloned().collect();
-    for id in ids {
-      if let Some(r) = decision_info.robots.get_mut(&id) {
-        self.offer_movement_mining_attack_option(id.to_string(), r);
-        self.offer_sell_option(id.to_string(), r);
+    for player in self.game_data.enemy_profiles.players() {
+      if let Some(profile) = self.game_data.enemy_profiles.profile(player) {
+        debug!("enemy player ({}): {}", player, profile);
       }
     }
-    
-    while self.round_data.balance > 0. {
-      //if !self.spend_money(&mut decision_info) {
-      //  break;
-
-      // we generated a wild error when sending the purchase item command - I really need to go to sleep and don't have any patience for fixing the skeleton left. So only robot purchases allowed.
-      if self.round_data.balance >= *self.round_data.item_prices.get(&TradeItemType::Robot).unwrap_or(&99999.) {
-        self.game_data.robot_buy_amount += 1;
-        self.round_data.balance -= *self.round_data.item_prices.get(&TradeItemType::Robot).unwrap_or(&99999.);
-      }
-      else {
-        break;
+    for resource_type in [MineableResourceType::COAL, MineableResourceType::IRON, MineableResourceType::GEM, MineableResourceType::GOLD, MineableResourceType::PLATIN] {
+      if let Some(prices) = self.game_data.price_history.resource(resource_type) {
+        debug!("{} price: {}", resource_type, prices);
       }
     }
+    if let Some(prices) = self.game_data.price_history.item(TradeItemType::Robot) {
+      debug!("robot price: {}", prices);
+    }
+    for (name, prices) in self.game_data.price_history.unknown() {
+      debug!("{} price (unknown tradable): {}", name, prices);
+    }
 
-    for (id, robot) in &mut decision_info.robots {
-      if let Some(robot) = self.game_data.robots.get_mut(id) {
-        robot.move_count += 1;
+    // purchases of the last round that never happened were taken off the balance all the same
+    let settlement = self.game_data.reconciliation.settle(round_number);
+    for missing in &settlement.missing {
+      warn!("Purchase failed: {}", missing);
+    }
+    if !settlement.refund.is_zero() {
+      info!("Refunding {} of failed purchases to the balance", settlement.refund);
+      self.round_data.balance += settlement.refund;
+    }
+
+    self.role_assigner.assign(&self.round_data, &mut self.game_data);
+    let budget = self.budget_allocator.allocate(self.round_data.balance);
+    info!("Budget for round {} ({:?}): {}", round_number, self.game_data.game_phase(), budget);
+    info!("Ledger so far: {}", self.game_data.ledger.totals());
+    let plan = self.strategy.plan_round(&self.round_data, &self.game_data, &budget);
+    self.budget_allocator.settle(&budget, plan.spent_on_upgrades, plan.savings_target);
+    self.game_data.robot_buy_amount = plan.robot_purchases;
+    // the budget never hands out more than the balance, if the plan spends more anyway the bank will refuse some of it
+    self.round_data.balance = match self.round_data.balance.decrease_by(&plan.spent) {
+      Ok(balance) => balance,
+      Err(error) => {
+        warn!("Round {} plans purchases beyond the balance: {}", round_number, error);
+        Money::zero()
+      }
+    };
+    let expected_purchases = self.expected_purchases(&plan);
+    self.game_data.reconciliation.expect(round_number, expected_purchases);
+
+    for (id, robot) in &plan.decisions.robots {
+      if let Some(robot_info) = self.game_data.robots.get_mut(id) {
+        robot_info.travel_target = robot.action.get_travel_target();
+        robot_info.plan = robot.action.get_plan();
       }
 
       robot.action.execute_command(game_service_rest_adapter.clone(), self.game_data.player_id.to_string(), robot.id.to_string()).await;
@@ -73,245 +110,68 @@ impl GameLogicService {
     if self.game_data.robot_buy_amount > 0 {
       execute_purchase_robots_command(game_service_rest_adapter.clone(), self.game_data.player_id.to_string(), self.game_data.robot_buy_amount).await;
     }
-  }
 
-  fn offer_movement_mining_attack_option(&mut self, robot_id: String, robot_decision: &mut RobotDecisionInfo) {
-    let mut set_action = false;
-    if let Some(robot_info) = self.game_data.robots.get(&robot_id) {
-      if let Some(robot) = self.round_data.robots.get(&robot_id) {
-        if robot.energy > 3 { // not sure how much energy we need for which action
-          if let Some(planet) = self.game_data.planets.get(&robot.planet_id) {
-            let mut known_neighbours = 0;
-            if let Some(resource) = planet.resource {
-              for (e_id, e) in &self.round_data.enemy_robots {
-                if e.planet_id == robot.planet_id {
-                  let weight: f32 = (robot_info.attack_damage - e.damage_level.get_attack_damage_value_for_level()) as f32;
-                
-                  if robot_decision.action.get_weight() < weight {
-                    let attack_option: Box<dyn Action + Send + Sync> = Box::new(AttackAction::new(weight, e_id.to_string()));
-                    robot_decision.action = attack_option;
-                    set_action = true;
-                  }
-                  break;
-                }
-              }
-              
-              let mut best_planet = Direction::Here;
-              let mut best_price = *self.round_data.resource_prices.get(&resource.resource_type).unwrap_or(&0.);
-              let mut best_planet_amount = resource.current_amount;
-
-              if let Some(p) = self.game_data.planets.get(&planet.north) {
-                self.evaluate_planet(robot.mining_level, Direction::North, p, &mut best_planet, &mut best_price, &mut best_planet_amount);
-                known_neighbours += 1;
-              }
-            
-              if let Some(p) = self.game_data.planets.get(&planet.south) {
-                self.evaluate_planet(robot.mining_level, Direction::South, p, &mut best_planet, &mut best_price, &mut best_planet_amount);
-                known_neighbours += 1;
-              }
-            
-              if let Some(p) = self.game_data.planets.get(&planet.west) {
-                self.evaluate_planet(robot.mining_level, Direction::West, p, &mut best_planet, &mut best_price, &mut best_planet_amount);
-                known_neighbours += 1;
-              }
-            
-              if let Some(p) = self.game_data.planets.get(&planet.east) {
-                self.evaluate_planet(robot.mining_level, Direction::East, p, &mut best_planet, &mut best_price, &mut best_planet_amount);
-                known_neighbours += 1;
-              }
-            
-              if best_planet_amount as f32 * best_price > 0. {
-                if best_planet != Direction::Here {
-                  let weight = best_price + best_planet_amount as f32;
-                
-                  if robot_decision.action.get_weight() < weight {
-                    let movement_option: Box<dyn Action + Send + Sync> = Box::new(MovementAction::new(weight, best_planet, planet.clone()));
-                    robot_decision.action = movement_option;
-                    set_action = true;
-                  }
-                } else if !robot_info.inventory.full {
-                  let weight = resource.current_amount /* LAST KNOWN, not *ACTUALLY* CURRENT */ as f32 + best_price;
-                
-                  if robot_decision.action.get_weight() < weight && (robot.mining_level as u8) >= (resource.resource_type as u8) {
-                    let mining_option: Box<dyn Action + Send + Sync> = Box::new(MineAction::new(weight, planet.id.to_string()));
-                    robot_decision.action = mining_option;
-                    set_action = true;
-                  }
-                }
-              }
-              else {
-                if robot_decision.action.get_weight() < 20000. {
-                  let a: Box<dyn Action + Send + Sync> = Box::new(MovementAction::new(20000., Direction::East, planet.clone()));
-                  robot_decision.action = a;
-                  set_action = true;
-                }
-              }
-            }
-            else {
-              if robot_decision.action.get_weight() < 20000. {
-                let a: Box<dyn Action + Send + Sync> = Box::new(MovementAction::new(20000., Direction::East, planet.clone()));
-                robot_decision.action = a;
-                set_action = true;
-              }
-            }
-
-            if known_neighbours < 4 && robot_info.move_count < 4 {
-              match robot_info.move_count {
-                0 => { 
-                    if planet.north != "" {
-                      robot_decision.action = Box::new(MovementAction::new(9999999., Direction::North, planet.clone()));
-                      set_action = true;
-                    }
-                  }
-                1 =>  {
-                    if planet.east != "" {
-                      robot_decision.action = Box::new(MovementAction::new(9999999., Direction::East, planet.clone()));
-                      set_action = true;
-                    }
-                  }
-                2 | 3 =>  {
-                    if planet.south != "" { 
-                      robot_decision.action = Box::new(MovementAction::new(9999999., Direction::South, planet.clone()));
-                      set_action = true;
-                    }
-                  }
-                _ => (),
-              }
-            }
-
-          }
-        }
-        else {
-          let a: Box<dyn Action + Send + Sync> = Box::new(RegenerateAction::new(9999999.));
-          robot_decision.action = a;
-          set_action = true;
-        }
-
-        if !set_action {
-          let a: Box<dyn Action + Send + Sync> = Box::new(RegenerateAction::new(9999.));
-          robot_decision.action = a;
-        }
-      }
-    }
+    // whatever we learn about enemies during the next round adds to what is left of today's danger
+    self.game_data.danger_map.decay();
+    self.game_data.combat_log.decay();
+    self.game_data.enemy_profiles.end_round(round_number);
   }
 
-  fn evaluate_planet(&self, mining_level: RobotLevel, dir: Direction, planet: &PersistentPlanetInfo, best_planet: &mut Direction, best_price: &mut f32, best_amount: &mut u32) {
-    if let Some(rsrc) = planet.resource {
-      if (mining_level as u8) < (rsrc.resource_type as u8) {
-        return;
-      }
-    }
-    if let Some(resource) = planet.resource {
-      let price = self.round_data.resource_prices.get(&resource.resource_type).unwrap_or(&0.);
-      if (price > best_price) {
-        *best_price = *price;
-        *best_planet = dir;
-        *best_amount = resource.current_amount; // LAST KNOWN, not *ACTUALLY* CURRENT
-      }
+  // the purchases the plan pays for, at the prices it planned with
+  fn expected_purchases(&self, plan: &RoundPlan) -> Vec<ExpectedPurchase> {
+    let mut purchases: Vec<ExpectedPurchase> = plan.decisions.robots.values()
+      .flat_map(|robot| [robot.action.get_purchase(), robot.upgrade_action.get_purchase()].into_iter().flatten().map(|item| (robot.id.clone(), item)))
+      .filter_map(|(robot_id, item)| self.round_data.item_prices.get(&item).map(|price| ExpectedPurchase { item, robot_id: Some(robot_id), amount: 1, unit_price: *price }))
+      .collect();
+    if let Some(price) = self.round_data.item_prices.get(&TradeItemType::Robot).filter(|_| plan.robot_purchases > 0) {
+      purchases.push(ExpectedPurchase { item: TradeItemType::Robot, robot_id: None, amount: plan.robot_purchases as u32, unit_price: *price });
     }
+    purchases
   }
 
-  fn offer_sell_option(&mut self, robot_id: String, robot_decision: &mut RobotDecisionInfo) {
-    if let Some(robot_info) = self.game_data.robots.get(&robot_id) {
-      if !robot_info.inventory.full {
-          let inventory_weight = robot_info.inventory.used_storage as f32 * 1000.;
-          
-          if inventory_weight > robot_decision.action.get_weight() {
-            let inventory_option: Box<dyn Action + Send + Sync> = Box::new(SellAction::new(inventory_weight as f32));
-            robot_decision.action = inventory_option;
-          }
-      }
-      else {
-        if 200000. > robot_decision.action.get_weight() {
-          robot_decision.action = Box::new(SellAction::new(200000.));
-        }
-      }
-    }
+  // Event Stuff
+  pub fn balance_update(&mut self, balance: Money) {
+    self.round_data.balance = balance;
+    self.game_data.reconciliation.booked(self.game_data.current_round);
   }
 
-  fn spend_money(&mut self, decision_info: &mut GameDecisionInfo) -> bool {
-    let mut lowest_level_robot = String::new();
-    let mut lowest_robot_level : RobotLevel = RobotLevel::LEVEL5;
-    let mut highest_robot_level : RobotLevel = RobotLevel::LEVEL0;
-    let ids: Vec<String> = self.game_data.robots.keys().cloned().collect();
-    let robot_count = ids.len();
-
-    for id in ids {
-      if let Some(robot) = self.round_data.robots.get(&id) {
-        if let Some(robot_decision_info) = decision_info.robots.get_mut(&id) {
-          if (robot_decision_info.has_upgrade) {
-            continue;
-          }
-        }
-
-        if ((robot.mining_level as u16) <= (lowest_robot_level as u16)) {
-          lowest_level_robot = id;
-          lowest_robot_level = robot.mining_level;
-        }
-
-        if ((robot.mining_level as u16) > (highest_robot_level as u16)) {
-          highest_robot_level = robot.mining_level;
-        }
+  /// Matches a purchase of the trading service against what the last rounds planned, `item` is None for names we don't know.
+  pub fn reconcile_purchase(&mut self, item: Option<TradeItemType>, name: &str, robot_id: Option<&str>, amount: u32, total: Money) {
+    if let Some(discrepancy) = self.game_data.reconciliation.bought(item, name, robot_id, amount, total) {
+      warn!("Purchase differs from the plan: {}", discrepancy);
+      // the round took the expected price off the balance, until the bank sends a new one it has to be corrected by hand
+      if let Discrepancy::PriceMismatch { correction: Some(correction), .. } = discrepancy {
+        self.round_data.balance = match correction {
+          Transfer::Credit(money) => self.round_data.balance + money,
+          Transfer::Debit(money) => self.round_data.balance.saturating_sub(&money),
+        };
       }
     }
+  }
 
-    let min_robot_count = ((highest_robot_level as usize) + 1) * 3;
+  /// Books the entry into the ledger in the current round.
+  pub fn record_transaction(&mut self, kind: EntryKind, item: String, amount: u32, unit_price: Money, total: Money, robot_id: Option<String>) {
+    let round = self.game_data.current_round;
+    self.game_data.ledger.record(LedgerEntry { round, kind, item, amount, unit_price, total, robot_id });
+  }
 
-    if lowest_robot_level == highest_robot_level && highest_robot_level != RobotLevel::LEVEL0 || robot_count < min_robot_count || lowest_level_robot.is_empty() {
-      if self.round_data.balance >= *self.round_data.item_prices.get(&TradeItemType::Robot).unwrap_or(&99999.) {
-        self.game_data.robot_buy_amount += 1;
-        self.round_data.balance -= *self.round_data.item_prices.get(&TradeItemType::Robot).unwrap_or(&99999.);
-        return true;
-      } else {
-        return false;
-      }
-    } else {
-      // buy upgrade for lowest_level_robot, if sufficient funds.
-      if let Some(item) = TradeItemType::get_next_level_item(RobotUpgradeType::Mining, (lowest_robot_level as u16)) {
-        if self.round_data.balance >= *self.round_data.item_prices.get(&item).unwrap_or(&99999.) {
-          if let Some(r) = decision_info.robots.get_mut(&lowest_level_robot) {
-            r.has_upgrade = true;
-            r.upgrade_action = Box::new(PurchaseAction::new(10000., item));
-            self.round_data.balance -= *self.round_data.item_prices.get(&item).unwrap_or(&99999.);
-            return true;
-          }
-        } else {
-          return false;
-        }
+  /// The earnings of every robot against what it cost, the returns of the upgrades and the totals.
+  pub fn report_ledger(&self) {
+    let ledger = &self.game_data.ledger;
+    info!("Ledger after {} entries: {}", ledger.entries().len(), ledger.totals());
+    info!("{} purchases differed from the plans", self.game_data.reconciliation.discrepancies());
+    for robot_id in ledger.robots_by_profit() {
+      if let Some(account) = ledger.account(robot_id) {
+        info!("robot ({}) {}", robot_id, account);
       }
     }
-
-    let ids: Vec<String> = self.game_data.robots.keys().cloned().collect();
-    for id in ids {
-      if let Some(robot_decision_info) = decision_info.robots.get_mut(&id) {
-        if let Some(robot_info) = self.game_data.robots.get(&id) {
-          if let Some(robot) = self.round_data.robots.get(&id) {
-            if !robot_decision_info.has_upgrade {
-              if let Some(health_price) = self.round_data.item_prices.get(&TradeItemType::HealthRestore) {
-                if self.round_data.balance >= *health_price && robot.health < 1 {
-                  let weight = 3000.;
-                  let item = TradeItemType::HealthRestore;
-                  
-                  robot_decision_info.has_upgrade = true;
-                  robot_decision_info.upgrade_action = Box::new(PurchaseAction::new(weight, item));
-                  self.round_data.balance -= *self.round_data.item_prices.get(&item).unwrap_or(&99999.);
-                  return true;
-                }
-              }
-            }
-          }
-        }
-      }
+    for upgrade_return in ledger.upgrade_returns() {
+      info!("{}", upgrade_return);
     }
-    return false;
-  }
-
-  // Event Stuff
-  pub fn balance_update(&mut self, balance: f32) {
-    self.round_data.balance = balance;
   }
 
-  pub fn update_item_price(&mut self, item: TradeItemType, price: f32) {
+  pub fn update_item_price(&mut self, item: TradeItemType, price: Money) {
+    self.game_data.price_history.record_item(item, self.game_data.current_round, price);
     if let Some(p) = self.round_data.item_prices.get_mut(&item) {
       *p = price;
     }
@@ -320,7 +180,8 @@ impl GameLogicService {
     }
   }
   
-  pub fn update_resource_price(&mut self, item: MineableResourceType, price: f32) {
+  pub fn update_resource_price(&mut self, item: MineableResourceType, price: Money) {
+    self.game_data.price_history.record_resource(item, self.game_data.current_round, price);
     if let Some(mut p) = self.round_data.resource_prices.get_mut(&item) {
       *p = price;
     }
@@ -329,10 +190,19 @@ impl GameLogicService {
     }
   }
 
+  /// Keeps the price of a tradable we don't know, so new tradables of the game service show up in the logs.
+  pub fn update_unknown_price(&mut self, name: &str, price: Money) {
+    if self.game_data.price_history.record_unknown(name, self.game_data.current_round, price) {
+      warn!("The trading service sells {} for {}, a tradable we don't know", name, price);
+    }
+  }
+
   pub fn save_robot(&mut self, robot: Robot) {
     if robot.player_id == self.game_data.player_id {
       let new_robot_info = PersistentRobotInfo::new(robot.robot_info.id.clone(), robot.player_id, robot.max_health, robot.max_energy, robot.energy_regen, robot.attack_damage, robot.mining_speed, robot.inventory);
       
+      self.game_data.world_map.anchor(&robot.robot_info.planet_id);
+      self.game_data.ledger.assign_robot(&new_robot_info.id);
       self.game_data.robots.insert(new_robot_info.id.clone(), new_robot_info);      
       self.round_data.robots.insert(robot.robot_info.id.clone(), robot.robot_info);
     }
@@ -348,7 +218,7 @@ impl GameLogicService {
       return;
     }
 
-    if let Some(mut robot) = self.round_data.robots.get_mut(&updated_robot.id) {
+    if let Some(robot) = self.round_data.robots.get_mut(&updated_robot.id) {
       if let Some(robot_info) = self.game_data.robots.get_mut(&updated_robot.id) {
         if updated_robot.damage_level != robot.damage_level {
           robot_info.attack_damage = updated_robot.damage_level.get_attack_damage_value_for_level();
@@ -370,22 +240,61 @@ impl GameLogicService {
         }
       }
 
-      robot = updated_robot;
+      *robot = updated_robot.clone();
     }
   }
 
-  pub fn update_enemy_robot(&mut self, updated_robot: &mut TransientRobotInfo) {
+  pub fn update_enemy_robot(&mut self, player: &str, updated_robot: &mut TransientRobotInfo) {
     if updated_robot.health == 0 {
+      self.game_data.enemy_profiles.record_destroyed(&updated_robot.id);
       self.round_data.enemy_robots.remove(&updated_robot.id);
       return;
     }
 
-    if let Some(mut robot) = self.round_data.enemy_robots.get_mut(&updated_robot.id) {
-      robot = updated_robot;
+    let mining = self.game_data.planets.get(&updated_robot.planet_id)
+      .and_then(|planet| planet.resource)
+      .is_some_and(|resource| !resource.is_empty() && (updated_robot.mining_level as u8) >= (resource.resource_type as u8));
+    self.game_data.enemy_profiles.record_robot(player, updated_robot, mining, self.game_data.current_round);
+
+    self.game_data.danger_map.record_enemy(&updated_robot.planet_id, updated_robot.damage_level.get_attack_damage_value_for_level());
+    self.round_data.enemy_robots.insert(updated_robot.id.clone(), updated_robot.clone());
+  }
+
+  pub fn update_robot_attacked(&mut self, attacker_id: String, target_id: String, available_health: u16, alive: bool) {
+    if let Some(robot) = self.round_data.robots.get_mut(&target_id) {
+      let damage_taken = robot.health.saturating_sub(available_health);
+      self.game_data.danger_map.record_attack(&robot.planet_id, damage_taken);
+      self.game_data.combat_log.record_attack_on_us(&attacker_id, damage_taken, !alive);
+      self.game_data.enemy_profiles.record_attack_on_us(&attacker_id, damage_taken);
+      robot.health = available_health;
+      let attacker_player = self.game_data.enemy_profiles.owner_of(&attacker_id).unwrap_or("unknown");
+      warn!("robot ({}) was attacked by ({}) of player ({}) on planet ({}) and lost {} health", target_id, attacker_id, attacker_player, robot.planet_id, damage_taken);
+
+      if !alive {
+        self.round_data.robots.remove(&target_id);
+        self.game_data.robots.remove(&target_id);
+      }
+    }
+    else if let Some(enemy) = self.round_data.enemy_robots.get_mut(&target_id) {
+      enemy.health = available_health;
+      if self.round_data.robots.contains_key(&attacker_id) {
+        self.game_data.combat_log.record_attack_by_us(&target_id, !alive);
+      }
+
+      if !alive {
+        let (kills, losses) = self.game_data.combat_log.score();
+        info!("robot ({}) destroyed enemy robot ({}), {} kills against {} losses so far", attacker_id, target_id, kills, losses);
+        self.game_data.enemy_profiles.record_destroyed(&target_id);
+        self.round_data.enemy_robots.remove(&target_id);
+      }
     }
   }
 
   pub fn update_inventory_add(&mut self, robot_id: String, mined_amount: u16, coal: u16, gem: u16, gold: u16, iron: u16, platin: u16) {
+    if let Some(robot) = self.round_data.robots.get(&robot_id) {
+      self.game_data.mining_history.record_own_extraction(&robot.planet_id, self.game_data.current_round, mined_amount as u32);
+    }
+
     if let Some(robot) = self.game_data.robots.get_mut(&robot_id) {
       robot.inventory.used_storage += mined_amount;
 
@@ -415,8 +324,9 @@ impl GameLogicService {
     }
   }
 
-  pub fn update_robot_location(&mut self, robot_id: String, new_planet: String, remaining_energy: u16) {
+  pub fn update_robot_location(&mut self, robot_id: String, new_planet: String, movement_difficulty: u8, remaining_energy: u16) {
     if let Some(r) = self.round_data.robots.get_mut(&robot_id) {
+      self.game_data.energy_model.record_move(movement_difficulty, r.energy, remaining_energy);
       r.planet_id = new_planet;
       r.energy = remaining_energy;
     }
@@ -429,10 +339,21 @@ impl GameLogicService {
   }
   pub fn update_robot_level(&mut self, robot_id: String, level: RobotLevel, upgrade: RobotUpgradeType) {
     if let Some(r) = self.round_data.robots.get_mut(&robot_id) {
+      r.set_level(&upgrade, level);
+    }
+
+    if let Some(robot_info) = self.game_data.robots.get_mut(&robot_id) {
       match upgrade {
-        RobotUpgradeType::Mining => r.mining_level = level,
-        _ => (),
-        // TODO add other upgrade types here, if used!
+        RobotUpgradeType::Storage => {
+          robot_info.inventory.max_storage = level.get_storage_value_for_level();
+          robot_info.inventory.full = robot_info.inventory.used_storage >= robot_info.inventory.max_storage;
+        }
+        RobotUpgradeType::Health => robot_info.max_health = level.get_max_health_value_for_level(),
+        RobotUpgradeType::Damage => robot_info.attack_damage = level.get_attack_damage_value_for_level(),
+        RobotUpgradeType::MiningSpeed => robot_info.mining_speed = level.get_mining_speed_value_for_level(),
+        RobotUpgradeType::MaxEnergy => robot_info.max_energy = level.get_max_energy_value_for_level(),
+        RobotUpgradeType::EnergyRegen => robot_info.energy_regen = level.get_energy_regen_value_for_level(),
+        RobotUpgradeType::Mining => (),
       }
     }
   }
@@ -442,25 +363,44 @@ impl GameLogicService {
     }
   }
 
-  pub fn update_planet(&mut self, planet_id: String, mined_amount: u32) {
+  pub fn update_planet(&mut self, planet_id: String, mined_amount: u32, remaining_amount: u32) {
+    self.game_data.mining_history.record_extraction(&planet_id, self.game_data.current_round, mined_amount);
+
     if let Some(planet) = self.round_data.planets.get_mut(&planet_id) {
-      if let Some(mut r) = planet.resource {
-        r.current_amount -= mined_amount;
-      
-        if let Some(planet_info) = self.game_data.planets.get_mut(&planet_id) {
-          if let Some(mut resource) = planet_info.resource {
-            resource.current_amount = r.current_amount;
-          }
-        }
+      if let Some(r) = planet.resource.as_mut() {
+        r.current_amount = remaining_amount;
       }
     }
+
+    if let Some(planet_info) = self.game_data.planets.get_mut(&planet_id) {
+      if let Some(resource) = planet_info.resource.as_mut() {
+        resource.current_amount = remaining_amount;
+      }
+    }
+
+    if let Some(rounds) = self.game_data.mining_history.rounds_until_empty(&planet_id, remaining_amount, self.game_data.current_round) {
+      debug!("planet ({}) runs out of resources in about {} round(s)", planet_id, rounds);
+    }
   }
 
   pub fn save_planet(&mut self, planet: TransientPlanetInfo, planet_info: PersistentPlanetInfo) {
     self.round_data.planets.insert(planet.id.clone(), planet.clone());
     self.game_data.planets.insert(planet_info.id.clone(), planet_info.clone());
 
-    print!("\n\n\nsaving planet ({}) with neigbours: \n north: {}\n east: {}\n south: {}\n west: {}\n\n\n", planet.id, planet_info.north, planet_info.east, planet_info.south, planet_info.west)
+    for inconsistency in self.game_data.world_map.add_planet(&planet_info) {
+      warn!("inconsistent map around planet ({}): {:?}", planet_info.id, inconsistency);
+    }
+    if let Some(bounds) = self.game_data.world_map.bounding_box() {
+      debug!("known world spans {}x{} cells in {} fragment(s)", bounds.width(), bounds.height(), self.game_data.world_map.fragment_count());
+    }
+
+    debug!("saving planet ({}) at {:?} with neighbours north: {}, east: {}, south: {}, west: {}", planet.id, self.game_data.world_map.position_of(&planet.id), planet_info.north, planet_info.east, planet_info.south, planet_info.west);
+  }
+
+  pub fn export_map(&self, name: &str) {
+    if let Some(exporter) = &self.map_exporter {
+      exporter.export(&MapSnapshot::capture(&self.game_data, &self.round_data), name);
+    }
   }
 
   pub fn clear_game(&mut self) {
@@ -470,5 +410,40 @@ impl GameLogicService {
     self.game_data.player_id = player_id;
 
     self.round_data = TransientData::new();
+    self.budget_allocator = BudgetAllocator::from_config();
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::robot::domain::robot::Inventory;
+
+  use super::*;
+
+  #[test]
+  fn test_update_robot_replaces_the_round_state() {
+    let mut game = GameLogicService::new();
+    game.round_data.robots.insert("r".to_string(), TransientRobotInfo::level0("r", "p", 20, 10));
+    game.game_data.robots.insert("r".to_string(), PersistentRobotInfo::fresh("r", Inventory::new(0, 0, 0, 0, 0, false, 0, 5)));
+
+    let mut updated_robot = TransientRobotInfo { storage_level: RobotLevel::LEVEL1, ..TransientRobotInfo::level0("r", "q", 12, 7) };
+    game.update_robot(&mut updated_robot);
+
+    let robot = &game.round_data.robots["r"];
+    assert_eq!((robot.planet_id.as_str(), robot.energy, robot.health, robot.storage_level), ("q", 12, 7, RobotLevel::LEVEL1));
+    assert_eq!(game.game_data.robots["r"].inventory.max_storage, RobotLevel::LEVEL1.get_storage_value_for_level());
+  }
+
+  #[test]
+  fn test_price_mismatches_correct_the_balance() {
+    let mut game = GameLogicService::new();
+    game.round_data.balance = Money::from_amount(300);
+    let robots = ExpectedPurchase { item: TradeItemType::Robot, robot_id: None, amount: 2, unit_price: Money::from_amount(100) };
+    game.game_data.reconciliation.expect(game.game_data.current_round, vec![robots]);
+
+    game.reconcile_purchase(Some(TradeItemType::Robot), "ROBOT", Some("new"), 1, Money::from_amount(120));
+    assert_eq!(game.round_data.balance, Money::from_amount(280));
+    game.reconcile_purchase(Some(TradeItemType::Robot), "ROBOT", Some("newer"), 1, Money::from_amount(90));
+    assert_eq!(game.round_data.balance, Money::from_amount(290));
   }
 }
@@ -0,0 +1,537 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tracing::{debug, warn};
+
+use crate::config::CONFIG;
+use crate::domainprimitives::command::action::{Action, AttackAction, MineAction, MovementAction, NoneAction, PlannedAction, PurchaseAction, RegenerateAction, SellAction};
+use crate::domainprimitives::location::direction::Direction;
+use crate::domainprimitives::purchasing::money::Money;
+use crate::domainprimitives::purchasing::trade_item_type::TradeItemType;
+use crate::game::application::budget_allocator::{Budget, SavingsTarget};
+use crate::game::application::combat_planner::{CombatPlanner, FocusFire};
+use crate::game::application::frontier_explorer::FrontierExplorer;
+use crate::game::application::lookahead_planner::LookaheadPlanner;
+use crate::game::application::strategy::{RoundPlan, Strategy};
+use crate::game::application::survival_policy::{Survival, SurvivalPolicy};
+use crate::game::application::task_allocator::{Task, TaskAllocator, TaskOffer};
+use crate::game::application::upgrade_planner::UpgradePlanner;
+use crate::game::application::utility::{UtilityModel, UtilityParameters, UtilityScore};
+use crate::game::domain::game_logic_info::{GameDecisionInfo, PersistentData, TransientData};
+use crate::game::domain::game_phase::GamePhase;
+use crate::planet::domain::planet::PersistentPlanetInfo;
+use crate::planet::domain::planet_graph::{PlanetGraph, Route, ShortestPaths};
+use crate::robot::domain::forward_model::{ForwardModel, PlannedStep};
+use crate::robot::domain::inventory_valuation::InventoryValuation;
+use crate::robot::domain::robot::{PersistentRobotInfo, RobotDecisionInfo, TransientRobotInfo};
+
+// not sure how much energy we need for mining and attacking, so keep a little reserve
+const MIN_ACTION_ENERGY: u16 = 3;
+// below this share of its max health a robot gets a health restore even where nobody attacks it
+const LOW_HEALTH_SHARE: f32 = 0.3;
+// the round a robot spends selling what it mined
+const SELL_ROUNDS: u32 = 1;
+
+// a task a robot has to travel for, scored without the travelling
+struct TravelOffer<'a> {
+  task: Task,
+  demand: u32,
+  score: UtilityScore,
+  hops: &'a [String],
+}
+
+/// Rustin Brber's original bot: mine the most valuable resource in reach, explore when nothing is, sell when the storage fills up
+/// and spend the fleet budget on new robots. Every option is scored by the `UtilityModel`, next to the plan over the next rounds
+/// the `LookaheadPlanner` finds for every miner.
+/// In the endgame nothing is bought anymore and robots only do what pays off - sold - before the last round.
+pub struct HeuristicStrategy {
+  parameters: UtilityParameters,
+  lookahead_rounds: u32,
+  lookahead_time_budget: Duration, // for all robots together
+}
+
+impl HeuristicStrategy {
+  pub fn new() -> Self {
+    Self {
+      parameters: UtilityParameters::new(),
+      lookahead_rounds: CONFIG.lookahead_rounds,
+      lookahead_time_budget: Duration::from_millis(CONFIG.lookahead_time_budget_ms),
+    }
+  }
+}
+
+impl Strategy for HeuristicStrategy {
+  fn name(&self) -> &'static str {
+    "heuristic"
+  }
+
+  fn plan_round(&self, round_data: &TransientData, game_data: &PersistentData, budget: &Budget) -> RoundPlan {
+    let endgame = game_data.game_phase() == GamePhase::Endgame;
+    let deadline = if endgame { game_data.remaining_rounds().map(u32::from) } else { None };
+    // plans have to be sold before the game ends
+    let horizon = deadline.map_or(self.lookahead_rounds, |rounds| self.lookahead_rounds.min(rounds.saturating_sub(SELL_ROUNDS)));
+    let mut round = HeuristicRound {
+      round_data,
+      game_data,
+      utility: UtilityModel::new(self.parameters, round_data, game_data),
+      valuation: InventoryValuation::new(round_data, game_data),
+      lookahead: LookaheadPlanner::new(horizon, self.lookahead_time_budget, self.parameters.route_commitment),
+      focus_fire: HashMap::new(),
+      deadline,
+      robot_purchases: 0,
+    };
+    let mut decision_info = GameDecisionInfo::new();
+
+    for id in game_data.robots.keys() {
+      let r = RobotDecisionInfo::new(id.clone(), Box::new(NoneAction::new()), Box::new(NoneAction::new()), false);
+      decision_info.robots.insert(id.clone(), r);
+    }
+
+    let combat_planner = CombatPlanner::new(round_data, game_data);
+    round.focus_fire = combat_planner.engagements(MIN_ACTION_ENERGY).iter().flat_map(|engagement| combat_planner.focus_fire(engagement)).collect();
+
+    let planet_graph = PlanetGraph::new(&game_data.planets);
+    let mut frontier_explorer = FrontierExplorer::new(&game_data.planets, &round_data.resource_prices);
+    // the auction of the last round decided where the robots are heading
+    for robot_info in game_data.robots.values() {
+      if let Some(target) = &robot_info.travel_target {
+        frontier_explorer.claim(&robot_info.id, target, &game_data.world_map);
+      }
+    }
+
+    let mut task_allocator = TaskAllocator::new();
+    let survival_policy = SurvivalPolicy::new(round_data, game_data);
+    let mut rescues = Vec::new();
+
+    // sorted, so that the same situation always leads to the same offers
+    let mut ids: Vec<String> = game_data.robots.keys().cloned().collect();
+    ids.sort();
+    for id in ids {
+      // robots losing their fight don't take part in the auction
+      if let (Some(robot_info), Some(robot)) = (game_data.robots.get(&id), round_data.robots.get(&id)) {
+        if let Some(survival) = survival_policy.plan(robot_info, robot) {
+          rescues.push((id, survival));
+          continue;
+        }
+      }
+      round.offer_movement_mining_attack_option(id.to_string(), &mut task_allocator, &planet_graph, &frontier_explorer);
+      round.offer_sell_option(id.to_string(), &mut task_allocator);
+    }
+    // energy restores for the travelling robots are paid from the restore budget
+    let restore_budget = budget.restores;
+    let assignment = task_allocator.assign(&mut decision_info, restore_budget);
+
+    let restore_left = round.rescue(rescues, &mut decision_info, assignment.budget);
+    let restore_left = round.buy_health_restores(&mut decision_info, restore_left);
+
+    let upgrade_budget = budget.upgrades;
+    let upgrade_planner = UpgradePlanner::new(round_data, game_data, &round.utility);
+    let savings_target = upgrade_planner.savings_target(upgrade_budget).filter(|_| !endgame);
+    let upgrade_left = match savings_target {
+      Some(_) => upgrade_budget,
+      None if endgame => upgrade_budget,
+      None => upgrade_planner.plan(&mut decision_info, upgrade_budget),
+    };
+
+    // without anything to save for, whatever the upgrades leave goes into new robots as well - robots bought in the endgame never pay back
+    let mut fleet_budget = budget.fleet;
+    if savings_target.is_none() {
+      fleet_budget += upgrade_left;
+    }
+    if endgame {
+      fleet_budget = Money::zero();
+    }
+    let fleet_start = fleet_budget;
+    if let Some(robot_price) = round_data.item_prices.get(&TradeItemType::Robot).filter(|_| round.can_buy(TradeItemType::Robot, None)) {
+      let robots = fleet_budget.can_buy_that_many_for(robot_price);
+      round.robot_purchases += robots as u16;
+      fleet_budget = fleet_budget.saturating_sub(&(*robot_price * robots));
+    }
+
+    RoundPlan {
+      decisions: decision_info,
+      robot_purchases: round.robot_purchases,
+      spent: restore_budget.saturating_sub(&restore_left) + upgrade_budget.saturating_sub(&upgrade_left) + fleet_start.saturating_sub(&fleet_budget),
+      spent_on_upgrades: upgrade_budget.saturating_sub(&upgrade_left),
+      savings_target: savings_target.map(|option| SavingsTarget { item: option.item, price: option.price }),
+    }
+  }
+}
+
+// the data of one round together with what it decided to buy
+struct HeuristicRound<'a> {
+  round_data: &'a TransientData,
+  game_data: &'a PersistentData,
+  utility: UtilityModel<'a>,
+  valuation: InventoryValuation<'a>,
+  lookahead: LookaheadPlanner,
+  focus_fire: HashMap<String, FocusFire>, // the target of every robot that shares its planet with enemies
+  deadline: Option<u32>, // rounds left in the endgame
+  robot_purchases: u16,
+}
+
+impl HeuristicRound<'_> {
+  // in the endgame only what pays off before the last round, selling included, is worth doing
+  fn in_time(&self, score: &UtilityScore) -> bool {
+    self.deadline.is_none_or(|rounds| score.rounds() + SELL_ROUNDS <= rounds)
+  }
+
+  // purchases that failed lately are not tried again for a while
+  fn can_buy(&self, item: TradeItemType, robot_id: Option<&str>) -> bool {
+    !self.game_data.reconciliation.is_blocked(item, robot_id, self.game_data.current_round)
+  }
+
+  fn offer_movement_mining_attack_option(&self, robot_id: String, task_allocator: &mut TaskAllocator, planet_graph: &PlanetGraph, frontier_explorer: &FrontierExplorer) {
+    if let Some(robot_info) = self.game_data.robots.get(&robot_id) {
+      if let Some(robot) = self.round_data.robots.get(&robot_id) {
+        if let Some(planet) = self.game_data.planets.get(&robot.planet_id) {
+          let danger_map = &self.game_data.danger_map;
+          let enemy_profiles = &self.game_data.enemy_profiles;
+          // routes go around danger and the territory of players that attack us
+          let paths = planet_graph.shortest_paths_with_penalty_from(&robot.planet_id, |planet_id| {
+            danger_map.excess_danger(planet_id, robot_info.attack_damage).ceil() as u32 + enemy_profiles.territory_penalty(planet_id, self.game_data.current_round)
+          });
+          let free_storage = robot_info.inventory.max_storage.saturating_sub(robot_info.inventory.used_storage) as u32;
+          let role = robot_info.role;
+
+          // attacking competes with mining the planet on the same scale
+          if let Some(focus_fire) = self.focus_fire.get(&robot_id).filter(|_| role.fights()) {
+            let score = self.utility.attack(robot, focus_fire);
+            let action = Box::new(AttackAction::new(score.per_round(), focus_fire.target_id.clone()));
+            task_allocator.offer(TaskOffer::new(robot_id.clone(), Task::Attack(focus_fire.target_id.clone()), 0, action).with_score(score));
+          }
+
+          if let Some(resource) = planet.resource.filter(|_| role.mines()) {
+            if !robot_info.inventory.full && (robot.mining_level as u8) >= (resource.resource_type as u8) {
+              if let Some(score) = self.utility.mine(robot_info, robot, &planet.id, resource).filter(|score| self.in_time(score)) {
+                let action: Box<dyn Action + Send + Sync> = if robot.energy > MIN_ACTION_ENERGY {
+                  Box::new(MineAction::new(score.per_round(), planet.id.to_string()))
+                } else {
+                  // stay on the deposit and mine it once we're back on our feet
+                  Box::new(RegenerateAction::new(score.clone().over(1).per_round()))
+                };
+                task_allocator.limit(Task::Mine(planet.id.clone()), resource.current_amount);
+                task_allocator.offer(TaskOffer::new(robot_id.clone(), Task::Mine(planet.id.clone()), free_storage, action).with_score(score));
+              }
+            }
+          }
+
+          if role.mines() {
+            for (dir, neighbour_id) in [(Direction::North, &planet.north), (Direction::South, &planet.south), (Direction::West, &planet.west), (Direction::East, &planet.east)] {
+              if let Some(p) = self.game_data.planets.get(neighbour_id) {
+                if let Some((score, amount)) = self.evaluate_planet(robot_info, robot, p) {
+                  task_allocator.limit(Task::Mine(p.id.clone()), amount);
+                  let travel = TravelOffer { task: Task::Mine(p.id.clone()), demand: free_storage, score, hops: std::slice::from_ref(&p.id) };
+                  self.offer_travel(&robot_id, robot, travel, task_allocator, |weight| Box::new(MovementAction::new(weight, dir, planet.clone())));
+                }
+              }
+            }
+
+            for (target_id, route, score, amount) in self.find_routes(robot_info, robot, &paths) {
+              task_allocator.limit(Task::Mine(target_id.clone()), amount);
+              let travel = TravelOffer { task: Task::Mine(target_id), demand: free_storage, score, hops: &route.hops };
+              self.offer_travel(&robot_id, robot, travel, task_allocator, |weight| Box::new(MovementAction::along_route(weight, planet.clone(), route.hops.clone())));
+            }
+
+            self.offer_plan(&robot_id, robot_info, robot, planet, task_allocator);
+          }
+
+          // exploring competes with mining on the same scale: part of the value we expect from an unknown planet, spread over the rounds to get there.
+          // One robot per target, the auction decides which, and the robot it sent there keeps the target in the next rounds
+          if let Some(target) = (role.explores() && self.deadline.is_none()).then(|| frontier_explorer.find_target(&robot_id, &planet.id, &paths, &self.game_data.world_map)).flatten() {
+            task_allocator.limit(Task::Explore(target.planet_id.clone()), 1);
+            let travel = TravelOffer { task: Task::Explore(target.planet_id.clone()), demand: 1, score: self.utility.explore(target.value), hops: &target.route.hops };
+            self.offer_travel(&robot_id, robot, travel, task_allocator, |weight| Box::new(MovementAction::along_route(weight, planet.clone(), target.route.hops.clone())));
+          }
+        }
+
+        // whatever happens, a robot that lost every auction still regenerates instead of idling
+        let score = self.utility.regenerate(robot);
+        task_allocator.offer(TaskOffer::new(robot_id.clone(), Task::Regenerate, 0, Box::new(RegenerateAction::new(score.per_round()))).with_score(score));
+      }
+    }
+  }
+
+  // moves right away if the robot has the energy for the next hop, otherwise it regenerates first - or buys an energy restore, whichever earns more per round.
+  fn offer_travel(&self, robot_id: &str, robot: &TransientRobotInfo, travel: TravelOffer, task_allocator: &mut TaskAllocator, movement: impl FnOnce(f32) -> Box<dyn Action + Send + Sync>) {
+    let TravelOffer { task, demand, score, hops } = travel;
+    let Some(target_id) = hops.last() else {
+      return;
+    };
+    let hop_difficulties: Vec<Option<u8>> = hops.iter().map(|hop| self.game_data.planets.get(hop).map(|p| p.movement_difficulty)).collect();
+    let plan = self.game_data.energy_model.plan_route(robot.energy, robot.energy_level, robot.energy_regen_level, &hop_difficulties);
+
+    if robot.energy >= plan.next_hop_cost {
+      let score = self.utility.travel(score, robot, hops.len() as u32, &plan, false);
+      if !self.in_time(&score) {
+        return;
+      }
+      let weight = score.per_round();
+      task_allocator.offer(TaskOffer::new(robot_id.to_string(), task, demand, movement(weight)).with_score(score));
+      return;
+    }
+
+    let regenerating = self.utility.travel(score.clone(), robot, hops.len() as u32, &plan, false);
+    let restore_price = self.round_data.item_prices.get(&TradeItemType::EnergyRestore).copied().unwrap_or(Money::zero());
+    if !restore_price.is_zero() && self.can_buy(TradeItemType::EnergyRestore, Some(robot_id)) {
+      let restoring = self.utility.travel(score, robot, hops.len() as u32, &plan, true);
+      if !self.in_time(&restoring) {
+        return;
+      }
+      let restore: Box<dyn Action + Send + Sync> = Box::new(PurchaseAction::new(restoring.per_round(), TradeItemType::EnergyRestore));
+      task_allocator.offer(TaskOffer::new(robot_id.to_string(), task.clone(), demand, restore).with_cost(restore_price).with_score(restoring));
+    }
+    if !self.in_time(&regenerating) {
+      return;
+    }
+    let regenerate = Box::new(RegenerateAction::before_travel(regenerating.per_round(), target_id.clone()));
+    task_allocator.offer(TaskOffer::new(robot_id.to_string(), task, demand, regenerate).with_score(regenerating));
+  }
+
+  // the first step of the robot's plan over the next rounds, the rest of the plan goes along with the action
+  fn offer_plan(&self, robot_id: &str, robot_info: &PersistentRobotInfo, robot: &TransientRobotInfo, planet: &PersistentPlanetInfo, task_allocator: &mut TaskAllocator) {
+    let model = ForwardModel::new(self.round_data, self.game_data, robot);
+    let Some(plan) = self.lookahead.plan(&model, &model.start(robot_info, robot), &robot_info.plan) else {
+      return;
+    };
+    let score = self.utility.plan(&plan);
+    let hops = plan.hops();
+    // the forward model knows nothing about enemies, routes through danger are left to the route finding
+    let dangerous = hops.iter().any(|hop| self.game_data.danger_map.excess_danger(hop, robot_info.attack_damage) > 0.);
+    if plan.value <= 0. || dangerous || !self.in_time(&score) {
+      return;
+    }
+
+    let weight = score.per_round();
+    let free_storage = robot_info.inventory.max_storage.saturating_sub(robot_info.inventory.used_storage) as u32;
+    let target_id = hops.last().unwrap_or(&planet.id).clone();
+    let heads_for_mining = plan.steps.iter().find(|step| !matches!(step, PlannedStep::Move(_) | PlannedStep::Regenerate)) == Some(&PlannedStep::Mine);
+    let (task, demand, action): (Task, u32, Box<dyn Action + Send + Sync>) = match &plan.steps[0] {
+      PlannedStep::Move(_) if heads_for_mining => (Task::Mine(target_id.clone()), free_storage, Box::new(MovementAction::along_route(weight, planet.clone(), hops))),
+      PlannedStep::Mine if robot.energy > MIN_ACTION_ENERGY => (Task::Mine(planet.id.clone()), free_storage, Box::new(MineAction::new(weight, planet.id.clone()))),
+      PlannedStep::Sell => (Task::Sell, 0, Box::new(SellAction::new(weight))),
+      PlannedStep::Regenerate if heads_for_mining && !hops.is_empty() => (Task::Mine(target_id.clone()), free_storage, Box::new(RegenerateAction::before_travel(weight, target_id.clone()))),
+      PlannedStep::Regenerate => (Task::Regenerate, 0, Box::new(RegenerateAction::new(weight))),
+      _ => return,
+    };
+
+    if let Some(resource) = self.game_data.planets.get(&target_id).and_then(|target| target.resource) {
+      task_allocator.limit(Task::Mine(target_id), resource.current_amount);
+    }
+    let action = Box::new(PlannedAction::new(action, plan.steps[1..].to_vec()));
+    task_allocator.offer(TaskOffer::new(robot_id.to_string(), task, demand, action).with_score(score));
+  }
+
+  // mining score and amount of a neighbouring planet worth moving to
+  fn evaluate_planet(&self, robot_info: &PersistentRobotInfo, robot: &TransientRobotInfo, planet: &PersistentPlanetInfo) -> Option<(UtilityScore, u32)> {
+    let resource = planet.resource?;
+    if (robot.mining_level as u8) < (resource.resource_type as u8) {
+      return None;
+    }
+    // a neighbour is one round away, no use going there if the others mine it empty in the meantime
+    if self.game_data.mining_history.is_depleted_before(&planet.id, resource.current_amount, self.game_data.current_round, 1) {
+      return None;
+    }
+
+    let score = self.utility.mine(robot_info, robot, &planet.id, resource)?;
+    Some((score, resource.current_amount)) // LAST KNOWN, not *ACTUALLY* CURRENT
+  }
+
+  // known planets at least two hops away worth mining, the travelling is scored when they are offered
+  fn find_routes(&self, robot_info: &PersistentRobotInfo, robot: &TransientRobotInfo, paths: &ShortestPaths) -> Vec<(String, Route, UtilityScore, u32)> {
+    let mut routes = Vec::new();
+
+    for (target_id, target) in &self.game_data.planets {
+      if let Some(resource) = target.resource {
+        if resource.is_empty() || (robot.mining_level as u8) < (resource.resource_type as u8) {
+          continue;
+        }
+
+        if let Some(route) = paths.route_to(target_id) {
+          // direct neighbours are already covered by evaluate_planet
+          if route.hops.len() < 2 {
+            continue;
+          }
+          if self.game_data.mining_history.is_depleted_before(target_id, resource.current_amount, self.game_data.current_round, route.hops.len() as u32) {
+            continue;
+          }
+
+          if let Some(score) = self.utility.mine(robot_info, robot, target_id, resource) {
+            routes.push((target_id.clone(), route, score, resource.current_amount));
+          }
+        }
+      }
+    }
+
+    routes
+  }
+
+  // sells the cargo, retreats or restores the health of robots in danger, returns what is left of the `budget`
+  fn rescue(&self, rescues: Vec<(String, Survival)>, decision_info: &mut GameDecisionInfo, budget: Money) -> Money {
+    let mut budget = budget;
+    let robot_price = self.round_data.item_prices.get(&TradeItemType::Robot).copied().map_or(0., Money::to_f32);
+
+    for (id, survival) in rescues {
+      let (Some(robot), Some(robot_info), Some(robot_decision_info)) = (self.round_data.robots.get(&id), self.game_data.robots.get(&id), decision_info.robots.get_mut(&id)) else {
+        continue;
+      };
+      // what we lose with the robot
+      let weight = robot_price + self.valuation.appraise(robot_info, Some(robot)).value.to_f32();
+      warn!("robot ({}) is in danger on planet ({}) with {} health left: {:?}", id, robot.planet_id, robot.health, survival);
+
+      match survival {
+        Survival::Sell => robot_decision_info.action = Box::new(SellAction::new(weight)),
+        Survival::Retreat(refuge) => {
+          if let Some(planet) = self.game_data.planets.get(&robot.planet_id) {
+            robot_decision_info.action = Box::new(MovementAction::along_route(weight, planet.clone(), vec![refuge]));
+          }
+        }
+        Survival::Restore => {
+          let price = self.round_data.item_prices.get(&TradeItemType::HealthRestore).copied().unwrap_or(Money::zero());
+          if !robot_decision_info.has_upgrade && budget.is_greater_equal_than(&price) && self.can_buy(TradeItemType::HealthRestore, Some(&id)) {
+            robot_decision_info.has_upgrade = true;
+            robot_decision_info.upgrade_action = Box::new(PurchaseAction::new(weight, TradeItemType::HealthRestore));
+            budget = budget.saturating_sub(&price);
+          }
+        }
+      }
+    }
+
+    budget
+  }
+
+  // a robot the enemies around it could destroy next round gets its health back before anything is spent on upgrades
+  fn buy_health_restores(&self, decision_info: &mut GameDecisionInfo, budget: Money) -> Money {
+    let mut budget = budget;
+    let Some(price) = self.round_data.item_prices.get(&TradeItemType::HealthRestore).copied().filter(|price| !price.is_zero()) else {
+      return budget;
+    };
+
+    let mut ids: Vec<String> = decision_info.robots.keys().cloned().collect();
+    ids.sort();
+    for id in ids {
+      let (Some(robot), Some(robot_info)) = (self.round_data.robots.get(&id), self.game_data.robots.get(&id)) else {
+        continue;
+      };
+      let critical_health = (robot_info.max_health as f32 * LOW_HEALTH_SHARE).max(self.game_data.danger_map.danger_at(&robot.planet_id));
+      if budget.is_less_than(&price) || robot.health as f32 > critical_health || !self.can_buy(TradeItemType::HealthRestore, Some(&id)) {
+        continue;
+      }
+
+      if let Some(robot_decision_info) = decision_info.robots.get_mut(&id) {
+        if !robot_decision_info.has_upgrade {
+          let weight = robot_info.max_health.saturating_sub(robot.health) as f32 * self.utility.health_price(robot);
+          robot_decision_info.has_upgrade = true;
+          robot_decision_info.upgrade_action = Box::new(PurchaseAction::new(weight, TradeItemType::HealthRestore));
+          budget = budget.saturating_sub(&price);
+        }
+      }
+    }
+
+    budget
+  }
+
+  fn offer_sell_option(&self, robot_id: String, task_allocator: &mut TaskAllocator) {
+    if let Some(robot_info) = self.game_data.robots.get(&robot_id) {
+      if robot_info.inventory.used_storage > 0 || robot_info.inventory.full {
+        let cargo = self.valuation.appraise(robot_info, self.round_data.robots.get(&robot_id));
+        // when there is no time left to fill up the storage, the cargo is sold whatever it holds
+        let sell_out = self.deadline.is_some_and(|rounds| rounds <= cargo.rounds_to_fill + SELL_ROUNDS);
+        let score = if sell_out { self.utility.sell_out(&cargo) } else { self.utility.sell(&cargo) };
+        debug!("robot ({}) carries cargo {}{}, selling: {}", robot_id, cargo, if sell_out { " with no time left to fill up" } else { "" }, score);
+        task_allocator.offer(TaskOffer::new(robot_id, Task::Sell, 0, Box::new(SellAction::new(score.per_round()))).with_score(score));
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::domainprimitives::location::mineable_resource::MineableResource;
+  use crate::domainprimitives::location::mineable_resource_type::MineableResourceType;
+  use crate::robot::domain::robot::Inventory;
+  use crate::transaction::domain::reconciliation::ExpectedPurchase;
+
+  use super::*;
+
+  fn budget(fleet: u64, upgrades: u64, restores: u64) -> Budget {
+    Budget { fleet: Money::from_amount(fleet), upgrades: Money::from_amount(upgrades), restores: Money::from_amount(restores), reserve: Money::zero(), savings_target: None }
+  }
+
+  #[test]
+  fn test_plans_an_action_for_every_robot_and_buys_robots() {
+    let mut game_data = PersistentData::new();
+    let mut round_data = TransientData::new();
+    let coal = MineableResource::from_type_amount_and_max_amount(MineableResourceType::COAL, 100, 100);
+    game_data.planets.insert("p".to_string(), PersistentPlanetInfo::new("p".to_string(), 1, Some(coal), String::new(), String::new(), String::new(), String::new()));
+    round_data.resource_prices.insert(MineableResourceType::COAL, Money::from_amount(5));
+    round_data.item_prices.insert(TradeItemType::Robot, Money::from_amount(100));
+    round_data.balance = Money::from_amount(250);
+
+    let robot_info = TransientRobotInfo::level0("r", "p", 20, 10);
+    game_data.robots.insert("r".to_string(), PersistentRobotInfo::fresh("r", Inventory::new(0, 0, 0, 0, 0, false, 0, 20)));
+    round_data.robots.insert("r".to_string(), robot_info);
+
+    let plan = HeuristicStrategy::new().plan_round(&round_data, &game_data, &budget(200, 50, 50));
+
+    // mining the coal right here: 20 coal at 5 each, 2 per round
+    assert_eq!(plan.decisions.robots["r"].action.get_weight(), 10.);
+    // the unspent upgrade money buys the second robot, the restore money is kept
+    assert_eq!(plan.robot_purchases, 2);
+    assert_eq!(plan.spent, Money::from_amount(200));
+  }
+
+  #[test]
+  fn test_sells_out_and_buys_nothing_in_the_endgame() {
+    let mut game_data = PersistentData::new();
+    let mut round_data = TransientData::new();
+    let coal = MineableResource::from_type_amount_and_max_amount(MineableResourceType::COAL, 100, 100);
+    game_data.planets.insert("p".to_string(), PersistentPlanetInfo::new("p".to_string(), 1, Some(coal), String::new(), String::new(), String::new(), String::new()));
+    game_data.current_round = 97;
+    game_data.max_rounds = 100;
+    round_data.resource_prices.insert(MineableResourceType::COAL, Money::from_amount(5));
+    round_data.item_prices.insert(TradeItemType::Robot, Money::from_amount(100));
+    round_data.balance = Money::from_amount(250);
+
+    let robot_info = TransientRobotInfo::level0("r", "p", 20, 10);
+    game_data.robots.insert("r".to_string(), PersistentRobotInfo::fresh("r", Inventory::new(10, 0, 0, 0, 0, false, 10, 20)));
+    round_data.robots.insert("r".to_string(), robot_info);
+
+    let plan = HeuristicStrategy::new().plan_round(&round_data, &game_data, &budget(200, 50, 50));
+
+    // filling the storage takes 5 more rounds, only 3 are left: the 10 coal are sold as they are
+    assert_eq!(plan.decisions.robots["r"].action.get_weight(), 50.);
+    assert_eq!(plan.robot_purchases, 0);
+    assert_eq!(plan.spent, Money::from_amount(0));
+    assert!(plan.savings_target.is_none());
+  }
+
+  #[test]
+  fn test_buys_a_health_restore_for_a_robot_in_danger() {
+    let mut game_data = PersistentData::new();
+    let mut round_data = TransientData::new();
+    game_data.planets.insert("p".to_string(), PersistentPlanetInfo::new("p".to_string(), 1, None, String::new(), String::new(), String::new(), String::new()));
+    game_data.danger_map.record_attack("p", 3);
+    round_data.item_prices.insert(TradeItemType::HealthRestore, Money::from_amount(30));
+    round_data.balance = Money::from_amount(50);
+
+    let robot_info = TransientRobotInfo::level0("r", "p", 20, 5);
+    game_data.robots.insert("r".to_string(), PersistentRobotInfo::fresh("r", Inventory::new(0, 0, 0, 0, 0, false, 0, 20)));
+    round_data.robots.insert("r".to_string(), robot_info);
+
+    let plan = HeuristicStrategy::new().plan_round(&round_data, &game_data, &budget(0, 0, 50));
+
+    // 6 danger from being attacked is more than the 5 health left
+    assert!(plan.decisions.robots["r"].has_upgrade);
+    assert_eq!(plan.spent, Money::from_amount(30));
+
+    // the restore never arrived, it is not tried again right away
+    let restore = ExpectedPurchase { item: TradeItemType::HealthRestore, robot_id: Some("r".to_string()), amount: 1, unit_price: Money::from_amount(30) };
+    game_data.reconciliation.expect(0, vec![restore]);
+    game_data.reconciliation.settle(1);
+    game_data.current_round = 1;
+    let plan = HeuristicStrategy::new().plan_round(&round_data, &game_data, &budget(0, 0, 50));
+    assert!(!plan.decisions.robots["r"].has_upgrade);
+    assert_eq!(plan.spent, Money::zero());
+  }
+}
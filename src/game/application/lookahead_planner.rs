@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::robot::domain::forward_model::{ForwardModel, PlannedStep, SimulatedRobot};
+
+// plans kept after every round of the search
+const BEAM_WIDTH: usize = 24;
+
+/// A sequence of steps for the next rounds and what the robot earns with it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+  pub steps: Vec<PlannedStep>,
+  pub value: f32, // money earned plus the value of the cargo gained, compared to doing nothing
+  pub committed: bool, // the rest of the plan the robot followed last round
+}
+
+impl Plan {
+  pub fn per_round(&self) -> f32 {
+    self.value / self.steps.len().max(1) as f32
+  }
+
+  /// The planets the robot moves through before it gets to work.
+  pub fn hops(&self) -> Vec<String> {
+    self.steps.iter()
+      .take_while(|step| matches!(step, PlannedStep::Move(_) | PlannedStep::Regenerate))
+      .filter_map(|step| match step {
+        PlannedStep::Move(planet_id) => Some(planet_id.clone()),
+        _ => None,
+      })
+      .collect()
+  }
+}
+
+// a plan in the making and where it got the robot
+struct Candidate {
+  steps: Vec<PlannedStep>,
+  state: SimulatedRobot,
+  value: f32,
+}
+
+/// Looks a few rounds ahead with a beam search over the `ForwardModel`: every round only the most valuable plans are expanded,
+/// so a robot sees that moving two hops and then mining for five rounds beats what is best for the next round alone.
+/// All robots share the time budget of a round, once it is used up the remaining robots plan fewer rounds ahead.
+pub struct LookaheadPlanner {
+  horizon: u32,
+  deadline: Instant,
+  commitment: f32, // factor on the plan a robot picked in an earlier round
+}
+
+impl LookaheadPlanner {
+  pub fn new(horizon: u32, time_budget: Duration, commitment: f32) -> Self {
+    Self {
+      horizon,
+      deadline: Instant::now() + time_budget,
+      commitment,
+    }
+  }
+
+  /// The best plan from `start`, or the rest of the `committed` plan if it is still possible and - weighed up by the commitment - earns as much per round.
+  pub fn plan(&self, model: &ForwardModel, start: &SimulatedRobot, committed: &[PlannedStep]) -> Option<Plan> {
+    if self.horizon == 0 {
+      return None;
+    }
+    let start_value = model.value(start);
+    let mut beam = vec![Candidate { steps: Vec::new(), state: start.clone(), value: start_value }];
+
+    for round in 0..self.horizon {
+      // the first round is always planned, it costs next to nothing
+      if round > 0 && Instant::now() >= self.deadline {
+        break;
+      }
+      // plans that end up in the same place with the same energy and storage are as good as their value
+      let mut expanded: HashMap<(String, u16, u32), Candidate> = HashMap::new();
+      for candidate in &beam {
+        for step in model.steps(&candidate.state) {
+          let state = model.apply(&candidate.state, &step);
+          let value = model.value(&state);
+          let key = (state.planet_id.clone(), state.energy, state.used_storage());
+          if expanded.get(&key).is_none_or(|best| best.value < value) {
+            let mut steps = candidate.steps.clone();
+            steps.push(step);
+            expanded.insert(key, Candidate { steps, state, value });
+          }
+        }
+      }
+
+      beam = expanded.into_values().collect();
+      // ties go to the plan that comes first, so the same situation always leads to the same plan
+      beam.sort_by(|a, b| b.value.total_cmp(&a.value).then_with(|| format!("{:?}", a.steps).cmp(&format!("{:?}", b.steps))));
+      beam.truncate(BEAM_WIDTH);
+    }
+
+    let best = beam.into_iter()
+      .map(|candidate| Plan { steps: candidate.steps, value: candidate.value - start_value, committed: false })
+      .max_by(|a, b| a.per_round().total_cmp(&b.per_round()))?;
+
+    let kept = (!committed.is_empty()).then(|| model.simulate(start, committed)).flatten()
+      .map(|state| Plan { steps: committed.to_vec(), value: model.value(&state) - start_value, committed: true });
+    match kept {
+      Some(kept) if kept.per_round() * self.commitment >= best.per_round() => Some(kept),
+      _ => Some(best),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::domainprimitives::location::mineable_resource::MineableResource;
+  use crate::domainprimitives::location::mineable_resource_type::MineableResourceType;
+  use crate::domainprimitives::purchasing::money::Money;
+  use crate::domainprimitives::purchasing::robot_level::RobotLevel;
+  use crate::game::domain::game_logic_info::{PersistentData, TransientData};
+  use crate::planet::domain::planet::PersistentPlanetInfo;
+  use crate::robot::domain::robot::{Inventory, PersistentRobotInfo, TransientRobotInfo};
+
+  use super::*;
+
+  #[test]
+  fn test_travels_to_a_rich_deposit_two_hops_away() {
+    let mut round_data = TransientData::new();
+    let mut game_data = PersistentData::new();
+    let coal = MineableResource::from_type_amount_and_max_amount(MineableResourceType::COAL, 2, 100);
+    let iron = MineableResource::from_type_amount_and_max_amount(MineableResourceType::IRON, 100, 100);
+    game_data.planets.insert("p".to_string(), PersistentPlanetInfo { resource: Some(coal), ..PersistentPlanetInfo::with_neighbours("p", "q", "", "", "") });
+    game_data.planets.insert("q".to_string(), PersistentPlanetInfo::with_neighbours("q", "t", "", "p", ""));
+    game_data.planets.insert("t".to_string(), PersistentPlanetInfo { resource: Some(iron), ..PersistentPlanetInfo::with_neighbours("t", "", "", "q", "") });
+    round_data.resource_prices.insert(MineableResourceType::COAL, Money::from_amount(5));
+    round_data.resource_prices.insert(MineableResourceType::IRON, Money::from_amount(20));
+
+    let robot_info = PersistentRobotInfo::fresh("r", Inventory::new(0, 0, 0, 0, 0, false, 0, 20));
+    let robot = TransientRobotInfo { mining_level: RobotLevel::LEVEL1, ..TransientRobotInfo::level0("r", "p", 20, 10) };
+    let model = ForwardModel::new(&round_data, &game_data, &robot);
+    let start = model.start(&robot_info, &robot);
+
+    // the coal here is gone after one round, the iron is worth the trip
+    let plan = LookaheadPlanner::new(7, Duration::from_secs(1), 1.).plan(&model, &start, &[]).unwrap();
+    assert_eq!(plan.hops(), vec!["q".to_string(), "t".to_string()]);
+    assert_eq!(plan.steps[2..].to_vec(), vec![PlannedStep::Mine; 5]);
+
+    // one hop later the robot keeps to the plan even if something else is about as good
+    let moved = model.apply(&start, &plan.steps[0]);
+    let kept = LookaheadPlanner::new(7, Duration::from_secs(1), 1.25).plan(&model, &moved, &plan.steps[1..]).unwrap();
+    assert!(kept.committed);
+    assert_eq!(kept.steps, plan.steps[1..].to_vec());
+
+    // nothing to plan without a horizon
+    assert!(LookaheadPlanner::new(0, Duration::from_secs(1), 1.).plan(&model, &start, &[]).is_none());
+  }
+}
@@ -0,0 +1,252 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::config::CONFIG;
+use crate::domainprimitives::location::grid_position::GridPosition;
+use crate::domainprimitives::location::mineable_resource::MineableResource;
+use crate::domainprimitives::location::mineable_resource_type::MineableResourceType;
+use crate::game::domain::game_logic_info::{PersistentData, TransientData};
+use crate::robot::domain::robot::TransientRobotInfo;
+
+const CELL_SIZE: i32 = 40;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetSnapshot {
+  pub id: String,
+  pub position: Option<GridPosition>,
+  pub movement_difficulty: u8,
+  pub resource: Option<MineableResource>,
+  pub danger: f32,
+  pub north: String,
+  pub east: String,
+  pub south: String,
+  pub west: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RobotSnapshot {
+  pub id: String,
+  pub planet_id: String,
+  pub position: Option<GridPosition>,
+  pub energy: u16,
+  pub health: u16,
+}
+
+/// Everything we know about the map at the end of a round, sorted by id so exports can be diffed.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MapSnapshot {
+  pub round: u16,
+  pub planets: Vec<PlanetSnapshot>,
+  pub robots: Vec<RobotSnapshot>,
+  pub enemy_robots: Vec<RobotSnapshot>,
+}
+
+impl MapSnapshot {
+  pub fn capture(game_data: &PersistentData, round_data: &TransientData) -> Self {
+    let mut planets: Vec<PlanetSnapshot> = game_data.planets.values()
+      .map(|planet| PlanetSnapshot {
+        id: planet.id.clone(),
+        position: game_data.world_map.position_of(&planet.id),
+        movement_difficulty: planet.movement_difficulty,
+        resource: planet.resource,
+        danger: game_data.danger_map.danger_at(&planet.id),
+        north: planet.north.clone(),
+        east: planet.east.clone(),
+        south: planet.south.clone(),
+        west: planet.west.clone(),
+      })
+      .collect();
+    planets.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let robot_snapshots = |robots: Vec<&TransientRobotInfo>| {
+      let mut snapshots: Vec<RobotSnapshot> = robots.into_iter()
+        .map(|robot| RobotSnapshot {
+          id: robot.id.clone(),
+          planet_id: robot.planet_id.clone(),
+          position: game_data.world_map.position_of(&robot.planet_id),
+          energy: robot.energy,
+          health: robot.health,
+        })
+        .collect();
+      snapshots.sort_by(|a, b| a.id.cmp(&b.id));
+      snapshots
+    };
+
+    Self {
+      round: game_data.current_round,
+      planets,
+      robots: robot_snapshots(round_data.robots.values().collect()),
+      enemy_robots: robot_snapshots(round_data.enemy_robots.values().collect()),
+    }
+  }
+
+  pub fn to_json(&self) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(self)
+  }
+
+  pub fn to_dot(&self) -> String {
+    let mut dot = String::from("digraph map {\n  node [shape=box];\n");
+
+    for planet in &self.planets {
+      let resource = match planet.resource {
+        Some(resource) => format!("\\n{} {}/{}", resource.resource_type, resource.current_amount, resource.max_amount),
+        None => String::new(),
+      };
+      let robots = self.robots.iter().filter(|r| r.planet_id == planet.id).count();
+      let enemies = self.enemy_robots.iter().filter(|r| r.planet_id == planet.id).count();
+      let _ = writeln!(dot, "  \"{}\" [label=\"{}\\ndifficulty {}{}\\nrobots {} / enemies {}\"];", planet.id, planet.id, planet.movement_difficulty, resource, robots, enemies);
+
+      for neighbour_id in [&planet.north, &planet.east, &planet.south, &planet.west] {
+        if !neighbour_id.is_empty() {
+          let _ = writeln!(dot, "  \"{}\" -> \"{}\";", planet.id, neighbour_id);
+        }
+      }
+    }
+
+    dot.push_str("}\n");
+    dot
+  }
+
+  /// Grid rendering of every planet with a known position, our robots in green, enemies in red.
+  pub fn to_svg(&self) -> String {
+    let positions = self.planets.iter().filter_map(|planet| planet.position);
+    let (min_x, min_y, max_x, max_y) = positions.fold((0, 0, 0, 0), |(min_x, min_y, max_x, max_y), p| {
+      (min_x.min(p.x), min_y.min(p.y), max_x.max(p.x), max_y.max(p.y))
+    });
+    let width = (max_x - min_x + 1) * CELL_SIZE;
+    let height = (max_y - min_y + 1) * CELL_SIZE;
+    let cell_origin = |position: &GridPosition| ((position.x - min_x) * CELL_SIZE, (position.y - min_y) * CELL_SIZE);
+
+    let mut svg = String::new();
+    let _ = writeln!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">", width, height, width, height);
+    let _ = writeln!(svg, "<rect width=\"{}\" height=\"{}\" fill=\"#222\"/>", width, height);
+
+    for planet in &self.planets {
+      if let Some(position) = &planet.position {
+        let (x, y) = cell_origin(position);
+        let fill = match planet.resource {
+          Some(resource) if !resource.is_empty() => resource_colour(resource.resource_type),
+          _ => "#666",
+        };
+        let _ = writeln!(svg, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"#000\"><title>{} (difficulty {})</title></rect>", x, y, CELL_SIZE, CELL_SIZE, fill, planet.id, planet.movement_difficulty);
+      }
+    }
+
+    for (robots, colour, offset) in [(&self.robots, "#2c2", CELL_SIZE / 3), (&self.enemy_robots, "#d22", 2 * CELL_SIZE / 3)] {
+      for robot in robots {
+        if let Some(position) = &robot.position {
+          let (x, y) = cell_origin(position);
+          let _ = writeln!(svg, "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"><title>{}</title></circle>", x + offset, y + CELL_SIZE / 2, CELL_SIZE / 8, colour, robot.id);
+        }
+      }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+  }
+}
+
+fn resource_colour(resource_type: MineableResourceType) -> &'static str {
+  match resource_type {
+    MineableResourceType::COAL => "#444",
+    MineableResourceType::IRON => "#a86",
+    MineableResourceType::GEM => "#4ad",
+    MineableResourceType::GOLD => "#db3",
+    MineableResourceType::PLATIN => "#ccd",
+  }
+}
+
+/// Writes the map as JSON, DOT and SVG into the configured directory, every `interval` rounds and once at game end.
+pub struct MapExporter {
+  directory: PathBuf,
+  interval: u16,
+}
+
+impl MapExporter {
+  pub fn new(directory: PathBuf, interval: u16) -> Self {
+    Self {
+      directory,
+      interval,
+    }
+  }
+
+  pub fn from_config() -> Option<Self> {
+    CONFIG.map_export_dir.as_ref().map(|directory| Self::new(PathBuf::from(directory), CONFIG.map_export_interval))
+  }
+
+  pub fn is_due(&self, round: u16) -> bool {
+    self.interval > 0 && round.is_multiple_of(self.interval)
+  }
+
+  pub fn export(&self, snapshot: &MapSnapshot, name: &str) {
+    if let Err(e) = self.write_files(snapshot, name) {
+      warn!("Failed to export map to {}: {}", self.directory.display(), e);
+      return;
+    }
+    info!("Exported map of round {} to {}", snapshot.round, self.directory.join(name).display());
+  }
+
+  fn write_files(&self, snapshot: &MapSnapshot, name: &str) -> std::io::Result<()> {
+    fs::create_dir_all(&self.directory)?;
+    fs::write(self.directory.join(format!("{}.json", name)), snapshot.to_json()?)?;
+    fs::write(self.directory.join(format!("{}.dot", name)), snapshot.to_dot())?;
+    fs::write(self.directory.join(format!("{}.svg", name)), snapshot.to_svg())?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::planet::domain::planet::PersistentPlanetInfo;
+
+  use super::*;
+
+  fn snapshot() -> MapSnapshot {
+    let mut game_data = PersistentData::new();
+    let mut round_data = TransientData::new();
+    let coal = MineableResource::from_type_amount_and_max_amount(MineableResourceType::COAL, 50, 100);
+
+    game_data.world_map.anchor("spawn");
+    for planet in [
+      PersistentPlanetInfo::new("spawn".to_string(), 1, None, String::new(), "e".to_string(), String::new(), String::new()),
+      PersistentPlanetInfo::new("e".to_string(), 2, Some(coal), String::new(), String::new(), "spawn".to_string(), String::new()),
+    ] {
+      game_data.world_map.add_planet(&planet);
+      game_data.planets.insert(planet.id.clone(), planet);
+    }
+
+    let robot = TransientRobotInfo::level0("r1", "e", 20, 10);
+    round_data.robots.insert(robot.id.clone(), robot);
+
+    MapSnapshot::capture(&game_data, &round_data)
+  }
+
+  #[test]
+  fn test_json_contains_planets_and_robots() {
+    let json: serde_json::Value = serde_json::from_str(&snapshot().to_json().unwrap()).unwrap();
+
+    assert_eq!(json["planets"][0]["id"], "e");
+    assert_eq!(json["planets"][0]["position"]["x"], 1);
+    assert_eq!(json["planets"][0]["resource"]["currentAmount"], 50);
+    assert_eq!(json["robots"][0]["planetId"], "e");
+  }
+
+  #[test]
+  fn test_dot_and_svg_rendering() {
+    let snapshot = snapshot();
+    let dot = snapshot.to_dot();
+    let svg = snapshot.to_svg();
+
+    assert!(dot.contains("\"spawn\" -> \"e\";"));
+    assert!(dot.contains("COAL 50/100"));
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"80\" height=\"40\""));
+    assert!(svg.contains("<circle cx=\"53\" cy=\"20\""));
+  }
+}
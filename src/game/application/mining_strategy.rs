@@ -0,0 +1,123 @@
+use crate::domainprimitives::command::action::{Action, MineAction, MovementAction, NoneAction, RegenerateAction, SellAction};
+use crate::domainprimitives::purchasing::money::Money;
+use crate::domainprimitives::purchasing::trade_item_type::TradeItemType;
+use crate::game::application::budget_allocator::Budget;
+use crate::game::application::strategy::{RoundPlan, Strategy};
+use crate::game::domain::game_logic_info::{GameDecisionInfo, PersistentData, TransientData};
+use crate::game::domain::game_phase::GamePhase;
+use crate::planet::domain::planet::PersistentPlanetInfo;
+use crate::planet::domain::planet_graph::PlanetGraph;
+use crate::robot::domain::robot::{PersistentRobotInfo, RobotDecisionInfo, TransientRobotInfo};
+
+// not sure how much energy we need for mining, so keep a little reserve
+const MIN_ACTION_ENERGY: u16 = 3;
+
+/// A baseline to measure the heuristic strategy against: every robot mines the closest deposit it can mine, sells once its storage
+/// is full and the fleet budget goes into new robots. Nothing is scored, explored, attacked or upgraded.
+pub struct MiningStrategy;
+
+impl MiningStrategy {
+  pub fn new() -> Self {
+    Self
+  }
+
+  fn can_mine(robot_info: &PersistentRobotInfo, robot: &TransientRobotInfo, planet: &PersistentPlanetInfo) -> bool {
+    planet.resource.is_some_and(|resource| !resource.is_empty() && (robot.mining_level as u8) >= (resource.resource_type as u8)) && !robot_info.inventory.full
+  }
+
+  fn decide(&self, robot_info: &PersistentRobotInfo, robot: &TransientRobotInfo, game_data: &PersistentData, planet_graph: &PlanetGraph) -> Box<dyn Action + Send + Sync> {
+    let Some(planet) = game_data.planets.get(&robot.planet_id) else {
+      return Box::new(RegenerateAction::new(0.));
+    };
+    if robot_info.inventory.full {
+      return Box::new(SellAction::new(1.));
+    }
+    if robot.energy <= MIN_ACTION_ENERGY {
+      return Box::new(RegenerateAction::new(0.));
+    }
+    if Self::can_mine(robot_info, robot, planet) {
+      return Box::new(MineAction::new(1., planet.id.clone()));
+    }
+
+    let paths = planet_graph.shortest_paths_from(&planet.id);
+    let closest = game_data.planets.values()
+      .filter(|target| Self::can_mine(robot_info, robot, target))
+      .filter_map(|target| paths.route_to(&target.id).map(|route| (route, target.id.as_str())))
+      .min_by(|(a, a_id), (b, b_id)| a.cost.cmp(&b.cost).then_with(|| a_id.cmp(b_id)));
+    match closest {
+      Some((route, _)) => Box::new(MovementAction::along_route(1., planet.clone(), route.hops)),
+      None if robot_info.inventory.used_storage > 0 => Box::new(SellAction::new(1.)),
+      None => Box::new(RegenerateAction::new(0.)),
+    }
+  }
+}
+
+impl Strategy for MiningStrategy {
+  fn name(&self) -> &'static str {
+    "mining"
+  }
+
+  fn plan_round(&self, round_data: &TransientData, game_data: &PersistentData, budget: &Budget) -> RoundPlan {
+    let planet_graph = PlanetGraph::new(&game_data.planets);
+    let mut decision_info = GameDecisionInfo::new();
+
+    for (id, robot_info) in &game_data.robots {
+      let action = match round_data.robots.get(id) {
+        Some(robot) => self.decide(robot_info, robot, game_data, &planet_graph),
+        None => Box::new(NoneAction::new()),
+      };
+      decision_info.robots.insert(id.clone(), RobotDecisionInfo::new(id.clone(), action, Box::new(NoneAction::new()), false));
+    }
+
+    // robots bought in the endgame never pay back
+    let mut robot_purchases = 0;
+    if game_data.game_phase() != GamePhase::Endgame {
+      if let Some(robot_price) = round_data.item_prices.get(&TradeItemType::Robot) {
+        robot_purchases = budget.fleet.can_buy_that_many_for(robot_price);
+      }
+    }
+    let spent = round_data.item_prices.get(&TradeItemType::Robot).map_or(Money::zero(), |robot_price| *robot_price * robot_purchases);
+
+    RoundPlan {
+      decisions: decision_info,
+      robot_purchases: robot_purchases as u16,
+      spent,
+      spent_on_upgrades: Money::zero(),
+      savings_target: None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::domainprimitives::location::mineable_resource::MineableResource;
+  use crate::domainprimitives::location::mineable_resource_type::MineableResourceType;
+  use crate::robot::domain::robot::Inventory;
+
+  use super::*;
+
+  #[test]
+  fn test_moves_to_the_closest_deposit_it_can_mine_and_buys_robots() {
+    let mut round_data = TransientData::new();
+    let mut game_data = PersistentData::new();
+    let coal = MineableResource::from_type_amount_and_max_amount(MineableResourceType::COAL, 100, 100);
+    let gold = MineableResource::from_type_amount_and_max_amount(MineableResourceType::GOLD, 100, 100);
+    game_data.planets.insert("p".to_string(), PersistentPlanetInfo { resource: Some(gold), ..PersistentPlanetInfo::with_neighbours("p", "", "q", "", "") });
+    game_data.planets.insert("q".to_string(), PersistentPlanetInfo::with_neighbours("q", "", "c", "", "p"));
+    game_data.planets.insert("c".to_string(), PersistentPlanetInfo { resource: Some(coal), ..PersistentPlanetInfo::with_neighbours("c", "", "", "", "q") });
+    game_data.robots.insert("r".to_string(), PersistentRobotInfo::fresh("r", Inventory::new(0, 0, 0, 0, 0, false, 0, 20)));
+    game_data.robots.insert("full".to_string(), PersistentRobotInfo::fresh("full", Inventory::new(20, 0, 0, 0, 0, true, 20, 20)));
+    round_data.robots.insert("r".to_string(), TransientRobotInfo::level0("r", "p", 20, 10));
+    round_data.robots.insert("full".to_string(), TransientRobotInfo::level0("full", "q", 20, 10));
+    round_data.item_prices.insert(TradeItemType::Robot, Money::from_amount(100));
+
+    let budget = Budget { fleet: Money::from_amount(250), upgrades: Money::from_amount(100), restores: Money::zero(), reserve: Money::zero(), savings_target: None };
+    let plan = MiningStrategy::new().plan_round(&round_data, &game_data, &budget);
+
+    // the gold here needs a better mining level
+    assert_eq!(plan.decisions.robots["r"].action.get_travel_target(), Some("c".to_string()));
+    assert_eq!(plan.decisions.robots["full"].action.get_weight(), 1.);
+    assert_eq!(plan.decisions.robots["full"].action.get_travel_target(), None);
+    assert_eq!((plan.robot_purchases, plan.spent), (2, Money::from_amount(200)));
+  }
+}
@@ -2,3 +2,16 @@ pub mod game_application_service;
 pub mod game_status_event_handler;
 pub mod round_status_event_handler;
 pub mod game_logic_service;
+pub mod frontier_explorer;
+pub mod map_exporter;
+pub mod task_allocator;
+pub mod strategy;
+pub mod heuristic_strategy;
+pub mod mining_strategy;
+pub mod utility;
+pub mod upgrade_planner;
+pub mod budget_allocator;
+pub mod combat_planner;
+pub mod survival_policy;
+pub mod role_assigner;
+pub mod lookahead_planner;
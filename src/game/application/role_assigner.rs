@@ -0,0 +1,121 @@
+use tracing::info;
+
+use crate::config::CONFIG;
+use crate::game::domain::game_logic_info::{PersistentData, TransientData};
+use crate::game::domain::game_phase::GamePhase;
+use crate::robot::domain::robot::PersistentRobotInfo;
+use crate::robot::domain::robot_role::RobotRole;
+
+// one more fighter for every that many enemy robots we have seen
+const ENEMIES_PER_FIGHTER: usize = 3;
+
+/// Keeps the roles of the fleet at the configured counts: explorers only until the endgame,
+/// more fighters the more enemies are revealed, everybody else mines. Roles only change when the counts do.
+pub struct RoleAssigner {
+  explorers: usize,
+  fighters: usize,
+}
+
+impl RoleAssigner {
+  pub fn new(explorers: usize, fighters: usize) -> Self {
+    Self {
+      explorers,
+      fighters,
+    }
+  }
+
+  pub fn from_config() -> Self {
+    Self::new(CONFIG.role_explorers, CONFIG.role_fighters)
+  }
+
+  /// (explorers, fighters) for the current fleet and game phase, at least one robot always mines.
+  pub fn wanted(&self, round_data: &TransientData, game_data: &PersistentData) -> (usize, usize) {
+    let fleet_size = game_data.robots.len();
+
+    let fighters = self.fighters.max(round_data.enemy_robots.len() / ENEMIES_PER_FIGHTER).min(fleet_size.saturating_sub(1));
+    let explorers = if game_data.game_phase() == GamePhase::Endgame { 0 } else { self.explorers.min(fleet_size.saturating_sub(1 + fighters)) };
+    (explorers, fighters)
+  }
+
+  pub fn assign(&self, round_data: &TransientData, game_data: &mut PersistentData) {
+    let (explorers, fighters) = self.wanted(round_data, game_data);
+    let mut robots: Vec<&mut PersistentRobotInfo> = game_data.robots.values_mut().collect();
+    robots.sort_by(|a, b| a.id.cmp(&b.id));
+
+    // the strongest robots fight, the ones with the emptiest storage lose the least by not mining
+    reassign(&mut robots, RobotRole::Fighter, fighters, |robot| robot.attack_damage as i32);
+    reassign(&mut robots, RobotRole::Explorer, explorers, |robot| -(robot.inventory.used_storage as i32));
+  }
+}
+
+// brings the role to `count` robots, the least suitable go back to mining first and the most suitable miners are picked first
+fn reassign(robots: &mut [&mut PersistentRobotInfo], role: RobotRole, count: usize, suitability: impl Fn(&PersistentRobotInfo) -> i32) {
+  let mut holders: Vec<usize> = (0..robots.len()).filter(|i| robots[*i].role == role).collect();
+  holders.sort_by_key(|i| suitability(robots[*i]));
+  while holders.len() > count {
+    let i = holders.remove(0);
+    info!("robot ({}) is a {:?} again", robots[i].id, RobotRole::Miner);
+    robots[i].role = RobotRole::Miner;
+  }
+
+  let mut candidates: Vec<usize> = (0..robots.len()).filter(|i| robots[*i].role == RobotRole::Miner).collect();
+  candidates.sort_by_key(|i| -suitability(robots[*i]));
+  for i in candidates.into_iter().take(count - holders.len()) {
+    info!("robot ({}) becomes a {:?}", robots[i].id, role);
+    robots[i].role = role;
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::robot::domain::robot::{Inventory, TransientRobotInfo};
+
+  use super::*;
+
+  fn data(attack_damages: &[u16]) -> (TransientData, PersistentData) {
+    let round_data = TransientData::new();
+    let mut game_data = PersistentData::new();
+    for (i, attack_damage) in attack_damages.iter().enumerate() {
+      let id = format!("r{}", i);
+      game_data.robots.insert(id.clone(), PersistentRobotInfo { attack_damage: *attack_damage, ..PersistentRobotInfo::fresh(&id, Inventory::new(i as u16, 0, 0, 0, 0, false, i as u16, 20)) });
+    }
+    (round_data, game_data)
+  }
+
+  fn roles(game_data: &PersistentData) -> Vec<RobotRole> {
+    let mut robots: Vec<&PersistentRobotInfo> = game_data.robots.values().collect();
+    robots.sort_by(|a, b| a.id.cmp(&b.id));
+    robots.iter().map(|robot| robot.role).collect()
+  }
+
+  #[test]
+  fn test_assigns_the_configured_counts_to_the_most_suitable_robots() {
+    let (round_data, mut game_data) = data(&[1, 5, 1, 1]);
+    RoleAssigner::new(1, 1).assign(&round_data, &mut game_data);
+
+    // the hardest hitter fights, the robot with the emptiest storage explores
+    assert_eq!(roles(&game_data), vec![RobotRole::Explorer, RobotRole::Fighter, RobotRole::Miner, RobotRole::Miner]);
+
+    // a lone robot mines
+    let (round_data, mut game_data) = data(&[5]);
+    RoleAssigner::new(1, 1).assign(&round_data, &mut game_data);
+    assert_eq!(roles(&game_data), vec![RobotRole::Miner]);
+  }
+
+  #[test]
+  fn test_more_fighters_with_more_enemies_and_no_explorers_in_the_endgame() {
+    let (mut round_data, mut game_data) = data(&[1, 5, 1, 1]);
+    for i in 0..6 {
+      let enemy = TransientRobotInfo::level0(&format!("e{}", i), "p", 20, 10);
+      round_data.enemy_robots.insert(enemy.id.clone(), enemy);
+    }
+    let assigner = RoleAssigner::new(1, 0);
+    assigner.assign(&round_data, &mut game_data);
+    assert_eq!(roles(&game_data), vec![RobotRole::Fighter, RobotRole::Fighter, RobotRole::Explorer, RobotRole::Miner]);
+
+    game_data.current_round = 90;
+    game_data.max_rounds = 100;
+    assigner.assign(&round_data, &mut game_data);
+    assert_eq!(roles(&game_data), vec![RobotRole::Fighter, RobotRole::Fighter, RobotRole::Miner, RobotRole::Miner]);
+  }
+}
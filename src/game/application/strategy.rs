@@ -0,0 +1,68 @@
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::config::CONFIG;
+use crate::domainprimitives::purchasing::money::Money;
+use crate::game::application::budget_allocator::{Budget, SavingsTarget};
+use crate::game::application::heuristic_strategy::HeuristicStrategy;
+use crate::game::application::mining_strategy::MiningStrategy;
+use crate::game::domain::game_logic_info::{GameDecisionInfo, PersistentData, TransientData};
+
+/// What a strategy decided for one round.
+pub struct RoundPlan {
+  pub decisions: GameDecisionInfo, // an action and maybe an upgrade per robot
+  pub robot_purchases: u16,
+  pub spent: Money, // money the decided item purchases cost
+  pub spent_on_upgrades: Money,
+  pub savings_target: Option<SavingsTarget>, // an upgrade the strategy holds its upgrade money back for
+}
+
+/// Decides what the whole fleet does in a round. Strategies only look at the game state and spend within the `Budget`,
+/// `GameLogicService` executes the plan.
+pub trait Strategy: Send + Sync {
+  fn name(&self) -> &'static str;
+  fn plan_round(&self, round_data: &TransientData, game_data: &PersistentData, budget: &Budget) -> RoundPlan;
+}
+
+pub const STRATEGIES: [&str; 2] = ["heuristic", "mining"];
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Unknown strategy {0}, pick one of {strategies}", strategies = STRATEGIES.join(", "))]
+pub struct UnknownStrategy(String);
+
+pub fn strategy_by_name(name: &str) -> Result<Box<dyn Strategy>, UnknownStrategy> {
+  match name {
+    "heuristic" => Ok(Box::new(HeuristicStrategy::new())),
+    "mining" => Ok(Box::new(MiningStrategy::new())),
+    _ => Err(UnknownStrategy(name.to_string())),
+  }
+}
+
+/// The strategy named by the `STRATEGY` environment variable, the heuristic one if it is unknown.
+pub fn from_config() -> Box<dyn Strategy> {
+  let strategy = strategy_by_name(&CONFIG.strategy).unwrap_or_else(|error| {
+    warn!("{}, falling back to the heuristic one", error);
+    Box::new(HeuristicStrategy::new())
+  });
+  info!("Playing with the {} strategy", strategy.name());
+  strategy
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_strategy_by_name() {
+    for name in STRATEGIES {
+      assert_eq!(strategy_by_name(name).map(|strategy| strategy.name()), Ok(name));
+    }
+  }
+
+  #[test]
+  fn test_unknown_strategy_is_reported() {
+    let error = strategy_by_name("unknown").err().unwrap();
+    assert_eq!(error, UnknownStrategy("unknown".to_string()));
+    assert_eq!(error.to_string(), "Unknown strategy unknown, pick one of heuristic, mining");
+  }
+}
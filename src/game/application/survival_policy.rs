@@ -0,0 +1,131 @@
+use crate::domainprimitives::purchasing::trade_item_type::TradeItemType;
+use crate::game::domain::game_logic_info::{PersistentData, TransientData};
+use crate::robot::domain::robot::{PersistentRobotInfo, TransientRobotInfo};
+
+// below this share of its max health a robot stops whatever it does and looks after itself
+const RETREAT_HEALTH_SHARE: f32 = 0.5;
+
+/// How a robot in danger saves itself, or at least its cargo.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Survival {
+  Sell, // the robot survives another round, so the cargo is turned into money first
+  Retreat(String), // the safest neighbouring planet
+  Restore, // nowhere to go, a health restore buys another round
+}
+
+/// Gets robots out of fights they lose before they are destroyed together with their cargo.
+pub struct SurvivalPolicy<'a> {
+  round_data: &'a TransientData,
+  game_data: &'a PersistentData,
+}
+
+impl<'a> SurvivalPolicy<'a> {
+  pub fn new(round_data: &'a TransientData, game_data: &'a PersistentData) -> Self {
+    Self {
+      round_data,
+      game_data,
+    }
+  }
+
+  // the damage the enemies on the planet deal per round, or what we remember of the planet if we see none
+  fn expected_damage(&self, planet_id: &str) -> f32 {
+    let enemy_damage: u32 = self.round_data.enemy_robots.values()
+      .filter(|enemy| enemy.planet_id == planet_id)
+      .map(|enemy| enemy.damage_level.get_attack_damage_value_for_level() as u32)
+      .sum();
+    (enemy_damage as f32).max(self.game_data.danger_map.danger_at(planet_id))
+  }
+
+  // an enemy on the planet that destroys the robot faster than the robot destroys it
+  fn meets_stronger_enemy(&self, robot_info: &PersistentRobotInfo, robot: &TransientRobotInfo) -> bool {
+    self.round_data.enemy_robots.values()
+      .filter(|enemy| enemy.planet_id == robot.planet_id)
+      .any(|enemy| {
+        let rounds_to_lose = (robot.health as u32).div_ceil(enemy.damage_level.get_attack_damage_value_for_level().max(1) as u32);
+        let rounds_to_win = (enemy.health as u32).div_ceil(robot_info.attack_damage.max(1) as u32);
+        rounds_to_lose < rounds_to_win
+      })
+  }
+
+  pub fn in_danger(&self, robot_info: &PersistentRobotInfo, robot: &TransientRobotInfo) -> bool {
+    ((robot.health as f32) < robot_info.max_health as f32 * RETREAT_HEALTH_SHARE && self.expected_damage(&robot.planet_id) > 0.)
+      || self.meets_stronger_enemy(robot_info, robot)
+  }
+
+  /// The neighbour with the least danger that is safer than where the robot is and that it has the energy to move to.
+  pub fn refuge(&self, robot: &TransientRobotInfo) -> Option<String> {
+    let planet = self.game_data.planets.get(&robot.planet_id)?;
+    let danger_here = self.expected_damage(&planet.id);
+
+    [&planet.north, &planet.east, &planet.south, &planet.west].into_iter()
+      .filter(|neighbour_id| !neighbour_id.is_empty())
+      .filter(|neighbour_id| {
+        // we don't know the difficulty of undiscovered planets, expect the easiest
+        let difficulty = self.game_data.planets.get(*neighbour_id).map(|p| p.movement_difficulty).unwrap_or(1);
+        robot.energy >= self.game_data.energy_model.move_cost(difficulty)
+      })
+      .map(|neighbour_id| (neighbour_id, self.expected_damage(neighbour_id)))
+      .filter(|(_, danger)| *danger < danger_here)
+      .min_by(|(a_id, a), (b_id, b)| a.total_cmp(b).then_with(|| a_id.cmp(b_id)))
+      .map(|(neighbour_id, _)| neighbour_id.clone())
+  }
+
+  /// What the robot should do to survive, None if it is not in danger or can't do anything about it.
+  pub fn plan(&self, robot_info: &PersistentRobotInfo, robot: &TransientRobotInfo) -> Option<Survival> {
+    if !self.in_danger(robot_info, robot) {
+      return None;
+    }
+    if robot_info.inventory.used_storage > 0 && (robot.health as f32) > self.expected_damage(&robot.planet_id) {
+      return Some(Survival::Sell);
+    }
+    if let Some(refuge) = self.refuge(robot) {
+      return Some(Survival::Retreat(refuge));
+    }
+    self.round_data.item_prices.contains_key(&TradeItemType::HealthRestore).then_some(Survival::Restore)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::domainprimitives::purchasing::money::Money;
+  use crate::domainprimitives::purchasing::robot_level::RobotLevel;
+  use crate::planet::domain::planet::PersistentPlanetInfo;
+  use crate::robot::domain::robot::Inventory;
+
+  use super::*;
+
+  fn data(used_storage: u16) -> (TransientData, PersistentData, PersistentRobotInfo) {
+    let mut round_data = TransientData::new();
+    let mut game_data = PersistentData::new();
+    game_data.planets.insert("p".to_string(), PersistentPlanetInfo::with_neighbours("p", "n", "", "s", ""));
+    game_data.planets.insert("n".to_string(), PersistentPlanetInfo::with_neighbours("n", "", "", "p", ""));
+    game_data.planets.insert("s".to_string(), PersistentPlanetInfo::with_neighbours("s", "p", "", "", ""));
+    game_data.danger_map.record_enemy("n", 4);
+    round_data.enemy_robots.insert("e".to_string(), TransientRobotInfo::level0("e", "p", 20, 20).with_damage(RobotLevel::LEVEL2));
+    let robot_info = PersistentRobotInfo::fresh("r", Inventory::new(used_storage, 0, 0, 0, 0, false, used_storage, 20));
+    (round_data, game_data, robot_info)
+  }
+
+  #[test]
+  fn test_sells_before_retreating_from_a_stronger_enemy() {
+    let (round_data, game_data, robot_info) = data(5);
+    let policy = SurvivalPolicy::new(&round_data, &game_data);
+
+    // 5 damage per round kills us in 2 rounds, we need 20 to destroy the enemy
+    assert_eq!(policy.plan(&robot_info, &TransientRobotInfo::level0("r", "p", 20, 10)), Some(Survival::Sell));
+    // the next hit would destroy the robot, the cargo is lost anyway
+    assert_eq!(policy.plan(&robot_info, &TransientRobotInfo::level0("r", "p", 20, 4)), Some(Survival::Retreat("s".to_string())));
+  }
+
+  #[test]
+  fn test_restores_when_there_is_nowhere_to_go() {
+    let (mut round_data, game_data, robot_info) = data(0);
+    let trapped = TransientRobotInfo::level0("r", "p", 0, 4);
+    assert_eq!(SurvivalPolicy::new(&round_data, &game_data).plan(&robot_info, &trapped), None);
+
+    round_data.item_prices.insert(TradeItemType::HealthRestore, Money::from_amount(30));
+    assert_eq!(SurvivalPolicy::new(&round_data, &game_data).plan(&robot_info, &trapped), Some(Survival::Restore));
+    // safe and sound on a quiet planet
+    assert_eq!(SurvivalPolicy::new(&round_data, &game_data).plan(&robot_info, &TransientRobotInfo::level0("r", "s", 20, 10)), None);
+  }
+}
@@ -0,0 +1,191 @@
+use std::collections::{HashMap, HashSet};
+
+use tracing::debug;
+
+use crate::domainprimitives::command::action::Action;
+use crate::domainprimitives::purchasing::money::Money;
+use crate::game::application::utility::UtilityScore;
+use crate::game::domain::game_logic_info::GameDecisionInfo;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Task {
+  Mine(String), // planet id, either the current planet or one we move to
+  Explore(String),
+  Attack(String), // enemy robot id
+  Sell,
+  Regenerate,
+}
+
+pub struct TaskOffer {
+  robot_id: String,
+  task: Task,
+  demand: u32,
+  cost: Money,
+  score: Option<UtilityScore>,
+  action: Box<dyn Action + Send + Sync>,
+}
+
+impl TaskOffer {
+  /// `demand` is how much of the task's capacity the robot uses up, e.g. its free storage for mining.
+  pub fn new(robot_id: String, task: Task, demand: u32, action: Box<dyn Action + Send + Sync>) -> Self {
+    Self {
+      robot_id,
+      task,
+      demand,
+      cost: Money::zero(),
+      score: None,
+      action,
+    }
+  }
+
+  /// Money the offer spends when it is assigned, e.g. for an item bought for the robot.
+  pub fn with_cost(mut self, cost: Money) -> Self {
+    self.cost = cost;
+    self
+  }
+
+  /// How the weight of the action came about, logged when the offer is assigned.
+  pub fn with_score(mut self, score: UtilityScore) -> Self {
+    self.score = Some(score);
+    self
+  }
+}
+
+/// The outcome of the auction: what is left to spend.
+pub struct Assignment {
+  pub budget: Money,
+}
+
+/// Collects what every robot could do this round and hands out the tasks for the whole fleet at once.
+/// Offers are auctioned off from the highest weight down, a task only goes to robots as long as it has capacity left,
+/// so the second robot heading for a small deposit gets its next best task instead.
+pub struct TaskAllocator {
+  offers: Vec<TaskOffer>,
+  capacities: HashMap<Task, u32>,
+}
+
+impl TaskAllocator {
+  pub fn new() -> Self {
+    Self {
+      offers: Vec::new(),
+      capacities: HashMap::new(),
+    }
+  }
+
+  /// Tasks without a limit can be taken by any number of robots.
+  pub fn limit(&mut self, task: Task, capacity: u32) {
+    self.capacities.entry(task).or_insert(capacity);
+  }
+
+  pub fn offer(&mut self, offer: TaskOffer) {
+    self.offers.push(offer);
+  }
+
+  /// Hands out the tasks, the assigned offers are paid from the `budget`.
+  pub fn assign(mut self, decision_info: &mut GameDecisionInfo, budget: Money) -> Assignment {
+    let mut budget = budget;
+    // ties go to the robot with the smaller id, so the same situation always leads to the same plan
+    self.offers.sort_by(|a, b| b.action.get_weight().total_cmp(&a.action.get_weight()).then_with(|| a.robot_id.cmp(&b.robot_id)));
+    let mut assigned = HashSet::new();
+
+    for offer in self.offers {
+      if assigned.contains(&offer.robot_id) || offer.cost.is_greater_than(&budget) {
+        continue;
+      }
+      if let Some(capacity) = self.capacities.get_mut(&offer.task) {
+        if *capacity == 0 {
+          continue;
+        }
+        *capacity = capacity.saturating_sub(offer.demand.max(1));
+      }
+
+      if let Some(robot) = decision_info.robots.get_mut(&offer.robot_id) {
+        if let Some(score) = &offer.score {
+          debug!("Robot {} takes {:?}: {}", offer.robot_id, offer.task, score);
+        }
+        robot.action = offer.action;
+        budget = budget.saturating_sub(&offer.cost);
+        assigned.insert(offer.robot_id);
+      }
+    }
+
+    Assignment {
+      budget,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::domainprimitives::command::action::{MineAction, NoneAction, RegenerateAction, SellAction};
+  use crate::robot::domain::robot::RobotDecisionInfo;
+
+  use super::*;
+
+  fn decision_info(robot_ids: &[&str]) -> GameDecisionInfo {
+    let mut decision_info = GameDecisionInfo::new();
+    for id in robot_ids {
+      decision_info.robots.insert(id.to_string(), RobotDecisionInfo::new(id.to_string(), Box::new(NoneAction::new()), Box::new(NoneAction::new()), false));
+    }
+    decision_info
+  }
+
+  #[test]
+  fn test_small_deposit_goes_to_one_robot() {
+    let mut decision_info = decision_info(&["a", "b"]);
+    let mut allocator = TaskAllocator::new();
+    allocator.limit(Task::Mine("p".to_string()), 10);
+    allocator.offer(TaskOffer::new("a".to_string(), Task::Mine("p".to_string()), 20, Box::new(MineAction::new(100., "p".to_string()))));
+    allocator.offer(TaskOffer::new("b".to_string(), Task::Mine("p".to_string()), 20, Box::new(MineAction::new(90., "p".to_string()))));
+    allocator.offer(TaskOffer::new("b".to_string(), Task::Regenerate, 0, Box::new(RegenerateAction::new(1.))));
+
+    allocator.assign(&mut decision_info, Money::zero());
+
+    assert_eq!(decision_info.robots["a"].action.get_weight(), 100.);
+    assert_eq!(decision_info.robots["b"].action.get_weight(), 1.);
+  }
+
+  #[test]
+  fn test_unlimited_tasks_and_best_offer_per_robot() {
+    let mut decision_info = decision_info(&["a", "b"]);
+    let mut allocator = TaskAllocator::new();
+    allocator.offer(TaskOffer::new("a".to_string(), Task::Sell, 0, Box::new(SellAction::new(50.))));
+    allocator.offer(TaskOffer::new("a".to_string(), Task::Regenerate, 0, Box::new(RegenerateAction::new(60.))));
+    allocator.offer(TaskOffer::new("b".to_string(), Task::Sell, 0, Box::new(SellAction::new(50.))));
+
+    allocator.assign(&mut decision_info, Money::zero());
+
+    assert_eq!(decision_info.robots["a"].action.get_weight(), 60.);
+    assert_eq!(decision_info.robots["b"].action.get_weight(), 50.);
+  }
+
+  #[test]
+  fn test_ties_go_to_the_smaller_robot_id() {
+    let mut decision_info = decision_info(&["a", "b"]);
+    let mut allocator = TaskAllocator::new();
+    allocator.limit(Task::Explore("x".to_string()), 1);
+    allocator.offer(TaskOffer::new("b".to_string(), Task::Explore("x".to_string()), 1, Box::new(RegenerateAction::new(30.))));
+    allocator.offer(TaskOffer::new("a".to_string(), Task::Explore("x".to_string()), 1, Box::new(RegenerateAction::new(30.))));
+    allocator.offer(TaskOffer::new("b".to_string(), Task::Sell, 0, Box::new(SellAction::new(10.))));
+
+    allocator.assign(&mut decision_info, Money::zero());
+
+    assert_eq!(decision_info.robots["a"].action.get_weight(), 30.);
+    assert_eq!(decision_info.robots["b"].action.get_weight(), 10.);
+  }
+
+  #[test]
+  fn test_offers_are_paid_from_the_budget() {
+    let mut decision_info = decision_info(&["a", "b"]);
+    let mut allocator = TaskAllocator::new();
+    allocator.offer(TaskOffer::new("a".to_string(), Task::Regenerate, 0, Box::new(RegenerateAction::new(80.))).with_cost(Money::from_amount(60)));
+    allocator.offer(TaskOffer::new("b".to_string(), Task::Regenerate, 0, Box::new(RegenerateAction::new(70.))).with_cost(Money::from_amount(60)));
+    allocator.offer(TaskOffer::new("b".to_string(), Task::Sell, 0, Box::new(SellAction::new(50.))));
+
+    let assignment = allocator.assign(&mut decision_info, Money::from_amount(100));
+
+    assert_eq!(assignment.budget, Money::from_amount(40));
+    assert_eq!(decision_info.robots["a"].action.get_weight(), 80.);
+    assert_eq!(decision_info.robots["b"].action.get_weight(), 50.);
+  }
+}
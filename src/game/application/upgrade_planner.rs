@@ -0,0 +1,286 @@
+use tracing::info;
+
+use crate::domainprimitives::command::action::PurchaseAction;
+use crate::domainprimitives::location::mineable_resource_type::MineableResourceType;
+use crate::domainprimitives::purchasing::money::Money;
+use crate::domainprimitives::purchasing::robot_level::RobotLevel;
+use crate::domainprimitives::purchasing::robot_upgrade_type::RobotUpgradeType;
+use crate::domainprimitives::purchasing::trade_item_type::TradeItemType;
+use crate::game::application::utility::UtilityModel;
+use crate::game::domain::game_logic_info::{GameDecisionInfo, PersistentData, TransientData};
+use crate::robot::domain::robot::TransientRobotInfo;
+use crate::robot::domain::robot_role::RobotRole;
+
+// rounds a robot spends selling at the end of every mining trip
+const SELL_ROUNDS: u32 = 1;
+// how long we expect the game to go on as long as we don't know the number of rounds
+const UNKNOWN_REMAINING_ROUNDS: u32 = 100;
+// how much better an upgrade we can't afford yet has to pay than the best affordable one before we save for it
+const SAVING_ADVANTAGE: f32 = 1.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpgradeOption {
+  pub robot_id: String,
+  pub item: TradeItemType,
+  pub price: Money,
+  pub gain_per_round: f32,
+}
+
+/// Weighs the next level of every upgrade on the path of every robot's role against its live price,
+/// an upgrade is only bought when it pays for itself before the game ends and pays better than another robot would.
+pub struct UpgradePlanner<'a> {
+  round_data: &'a TransientData,
+  game_data: &'a PersistentData,
+  utility: &'a UtilityModel<'a>,
+  remaining_rounds: u32,
+}
+
+impl<'a> UpgradePlanner<'a> {
+  pub fn new(round_data: &'a TransientData, game_data: &'a PersistentData, utility: &'a UtilityModel<'a>) -> Self {
+    let remaining_rounds = game_data.remaining_rounds().map(u32::from).unwrap_or(UNKNOWN_REMAINING_ROUNDS);
+
+    Self {
+      round_data,
+      game_data,
+      utility,
+      remaining_rounds,
+    }
+  }
+
+  // what a robot earns per round when it keeps filling its storage and selling it
+  fn mining_income(&self, resource_type: MineableResourceType, mining_speed: u16, storage: u16) -> f32 {
+    let price = self.round_data.resource_price(resource_type);
+    let storage = storage.max(1) as u32;
+    let rounds = storage.div_ceil(mining_speed.max(1) as u32) + SELL_ROUNDS;
+    price * storage as f32 / rounds as f32
+  }
+
+  // the damage the strongest enemy on the planet or next to it deals per round, 0 without any
+  fn strongest_enemy_damage(&self, planet_id: &str) -> f32 {
+    let neighbours = self.game_data.planets.get(planet_id).map(|planet| [planet.north.as_str(), planet.east.as_str(), planet.south.as_str(), planet.west.as_str()]);
+    self.round_data.enemy_robots.values()
+      .filter(|enemy| enemy.planet_id == planet_id || neighbours.is_some_and(|neighbours| !enemy.planet_id.is_empty() && neighbours.contains(&enemy.planet_id.as_str())))
+      .map(|enemy| enemy.damage_level.get_attack_damage_value_for_level())
+      .max()
+      .unwrap_or(0) as f32
+  }
+
+  /// What the next level of the upgrade earns the robot per round, 0 if it is maxed out.
+  pub fn gain_per_round(&self, robot: &TransientRobotInfo, role: RobotRole, upgrade: &RobotUpgradeType) -> f32 {
+    let level = robot.level_for(upgrade);
+    let Ok(next_level) = level.get_next_level() else {
+      return 0.;
+    };
+    let (mining_share, moving_share, fighting_share) = role.time_shares();
+
+    let resource_type = robot.mining_level.get_mineable_resource_for_level();
+    let mining_speed = robot.mining_speed_level.get_mining_speed_value_for_level();
+    let storage = robot.storage_level.get_storage_value_for_level();
+    let income = self.mining_income(resource_type, mining_speed, storage);
+
+    match upgrade {
+      RobotUpgradeType::Mining => mining_share * (self.mining_income(next_level.get_mineable_resource_for_level(), mining_speed, storage) - income),
+      RobotUpgradeType::MiningSpeed => mining_share * (self.mining_income(resource_type, next_level.get_mining_speed_value_for_level(), storage) - income),
+      RobotUpgradeType::Storage => mining_share * (self.mining_income(resource_type, mining_speed, next_level.get_storage_value_for_level()) - income),
+      RobotUpgradeType::EnergyRegen => {
+        let extra_regen = next_level.get_energy_regen_value_for_level() - level.get_energy_regen_value_for_level();
+        moving_share * extra_regen as f32 * self.utility.energy_price(robot)
+      }
+      RobotUpgradeType::MaxEnergy => {
+        // the extra energy saves a regeneration round every time the robot runs dry
+        let max_energy = next_level.get_max_energy_value_for_level();
+        let extra_energy = max_energy - level.get_max_energy_value_for_level();
+        let refills_per_round = robot.energy_regen_level.get_energy_regen_value_for_level() as f32 / max_energy as f32;
+        moving_share * extra_energy as f32 * refills_per_round * self.utility.energy_price(robot)
+      }
+      RobotUpgradeType::Health => {
+        // only the damage the robot actually takes is worth the health, when the planet was quiet so far that of the enemies around
+        let extra_health = next_level.get_max_health_value_for_level() - level.get_max_health_value_for_level();
+        let expected_damage = self.game_data.danger_map.danger_at(&robot.planet_id).max(fighting_share * self.strongest_enemy_damage(&robot.planet_id));
+        (extra_health as f32).min(expected_damage) * self.utility.health_price(robot)
+      }
+      RobotUpgradeType::Damage => {
+        let extra_damage = next_level.get_attack_damage_value_for_level() - level.get_attack_damage_value_for_level();
+        fighting_share * extra_damage as f32 * self.utility.health_price(robot)
+      }
+    }
+  }
+
+  /// The next level of every upgrade on the path of the robot's role that has a price and a positive gain, the best return on investment first.
+  pub fn options(&self) -> Vec<UpgradeOption> {
+    let mut options = Vec::new();
+
+    for (robot_id, robot) in &self.round_data.robots {
+      let role = self.game_data.robots.get(robot_id).map(|robot_info| robot_info.role).unwrap_or(RobotRole::Miner);
+      let upgrade_path = role.upgrade_path();
+      for upgrade in RobotUpgradeType::get_all_types().into_iter().filter(|upgrade| upgrade_path.contains(upgrade)) {
+        let level = robot.level_for(&upgrade) as u16;
+        let gain_per_round = self.gain_per_round(robot, role, &upgrade);
+        let Some(item) = TradeItemType::get_next_level_item(upgrade, level).filter(|item| !self.game_data.reconciliation.is_blocked(*item, Some(robot_id), self.game_data.current_round)) else {
+          continue;
+        };
+        let Some(price) = self.round_data.item_prices.get(&item).copied().filter(|price| !price.is_zero()) else {
+          continue;
+        };
+        if gain_per_round > 0. {
+          options.push(UpgradeOption { robot_id: robot_id.clone(), item, price, gain_per_round });
+        }
+      }
+    }
+
+    options.sort_by(|a, b| self.return_on_investment(b).total_cmp(&self.return_on_investment(a)).then_with(|| a.robot_id.cmp(&b.robot_id)));
+    options
+  }
+
+  /// Money earned until the game ends per money spent.
+  pub fn return_on_investment(&self, option: &UpgradeOption) -> f32 {
+    option.gain_per_round * self.remaining_rounds as f32 / option.price.to_f32()
+  }
+
+  // what another robot mining coal would return on its price
+  fn new_robot_return(&self) -> f32 {
+    let Some(price) = self.round_data.item_prices.get(&TradeItemType::Robot).copied().filter(|price| !price.is_zero()) else {
+      return 0.;
+    };
+    let level = RobotLevel::LEVEL0;
+    let income = self.mining_income(level.get_mineable_resource_for_level(), level.get_mining_speed_value_for_level(), level.get_storage_value_for_level());
+    income * self.remaining_rounds as f32 / price.to_f32()
+  }
+
+  // worth buying at all, whatever it costs
+  fn is_worth_it(&self, option: &UpgradeOption, new_robot_return: f32) -> bool {
+    let return_on_investment = self.return_on_investment(option);
+    return_on_investment > 1. && return_on_investment >= new_robot_return
+  }
+
+  /// The best upgrade if it costs more than the `budget` and pays clearly better than anything affordable, worth saving for.
+  pub fn savings_target(&self, budget: Money) -> Option<UpgradeOption> {
+    let new_robot_return = self.new_robot_return();
+    let options: Vec<UpgradeOption> = self.options().into_iter().filter(|option| self.is_worth_it(option, new_robot_return)).collect();
+    let best = options.first()?;
+    if budget.is_greater_equal_than(&best.price) {
+      return None;
+    }
+
+    let best_affordable = options.iter().find(|option| budget.is_greater_equal_than(&option.price));
+    match best_affordable {
+      Some(affordable) if self.return_on_investment(best) < SAVING_ADVANTAGE * self.return_on_investment(affordable) => None,
+      _ => Some(best.clone()),
+    }
+  }
+
+  /// Buys the best upgrades out of the `budget`, at most one per robot, and returns what is left.
+  pub fn plan(&self, decision_info: &mut GameDecisionInfo, budget: Money) -> Money {
+    let mut budget = budget;
+    let new_robot_return = self.new_robot_return();
+
+    for option in self.options() {
+      if !self.is_worth_it(&option, new_robot_return) || option.price.is_greater_than(&budget) {
+        continue;
+      }
+      let return_on_investment = self.return_on_investment(&option);
+      if let Some(robot) = decision_info.robots.get_mut(&option.robot_id) {
+        if robot.has_upgrade {
+          continue;
+        }
+        info!("Upgrading robot ({}) with {:?}, returns {:.1} times its price", option.robot_id, option.item, return_on_investment);
+        robot.has_upgrade = true;
+        robot.upgrade_action = Box::new(PurchaseAction::new(return_on_investment, option.item));
+        budget = budget.saturating_sub(&option.price);
+      }
+    }
+
+    budget
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::domainprimitives::command::action::NoneAction;
+  use crate::game::application::utility::UtilityParameters;
+  use crate::planet::domain::planet::PersistentPlanetInfo;
+  use crate::robot::domain::robot::RobotDecisionInfo;
+
+  use super::*;
+
+  fn data() -> (TransientData, PersistentData) {
+    let mut round_data = TransientData::new();
+    let mut game_data = PersistentData::new();
+    game_data.current_round = 10;
+    game_data.max_rounds = 60;
+    round_data.resource_prices.insert(MineableResourceType::COAL, Money::from_amount(5));
+    round_data.resource_prices.insert(MineableResourceType::IRON, Money::from_amount(15));
+
+    let robot = TransientRobotInfo::level0("r", "p", 20, 10);
+    round_data.robots.insert("r".to_string(), robot);
+    (round_data, game_data)
+  }
+
+  #[test]
+  fn test_gain_depends_on_the_role() {
+    let (round_data, game_data) = data();
+    let utility = UtilityModel::new(UtilityParameters::new(), &round_data, &game_data);
+    let planner = UpgradePlanner::new(&round_data, &game_data, &utility);
+    let robot = &round_data.robots["r"];
+
+    // 20 coal in 10 rounds + 1 to sell, iron is worth three times as much
+    let coal_income = 100. / 11.;
+    assert!((planner.gain_per_round(robot, RobotRole::Miner, &RobotUpgradeType::Mining) - 2. * coal_income).abs() < 0.001);
+    assert_eq!(planner.gain_per_round(robot, RobotRole::Fighter, &RobotUpgradeType::Mining), 0.);
+    assert!(planner.gain_per_round(robot, RobotRole::Explorer, &RobotUpgradeType::EnergyRegen) > planner.gain_per_round(robot, RobotRole::Miner, &RobotUpgradeType::EnergyRegen));
+  }
+
+  #[test]
+  fn test_health_gain_depends_on_the_enemies_around() {
+    let (mut round_data, mut game_data) = data();
+    game_data.planets.insert("p".to_string(), PersistentPlanetInfo::with_neighbours("p", "", "q", "", ""));
+    let utility = UtilityModel::new(UtilityParameters::new(), &round_data, &game_data);
+    let planner = UpgradePlanner::new(&round_data, &game_data, &utility);
+    let robot = TransientRobotInfo { health_level: RobotLevel::LEVEL1, ..TransientRobotInfo::level0("s", "p", 20, 25) };
+    assert_eq!(planner.gain_per_round(&robot, RobotRole::Fighter, &RobotUpgradeType::Health), 0.);
+
+    round_data.enemy_robots.insert("e".to_string(), TransientRobotInfo::level0("e", "q", 20, 10).with_damage(RobotLevel::LEVEL2));
+    let utility = UtilityModel::new(UtilityParameters::new(), &round_data, &game_data);
+    let planner = UpgradePlanner::new(&round_data, &game_data, &utility);
+
+    // the enemy next door deals 5 a round whatever health the robot has
+    let gain = planner.gain_per_round(&round_data.robots["r"], RobotRole::Fighter, &RobotUpgradeType::Health);
+    assert_eq!(gain, 5. * utility.health_price(&robot));
+    assert_eq!(planner.gain_per_round(&robot, RobotRole::Fighter, &RobotUpgradeType::Health), gain);
+  }
+
+  #[test]
+  fn test_plans_the_best_return_within_budget() {
+    let (mut round_data, game_data) = data();
+    round_data.item_prices.insert(TradeItemType::Mining1, Money::from_amount(50));
+    round_data.item_prices.insert(TradeItemType::MiningSpeed1, Money::from_amount(50));
+    round_data.item_prices.insert(TradeItemType::Storage1, Money::from_amount(5000));
+    let utility = UtilityModel::new(UtilityParameters::new(), &round_data, &game_data);
+    let planner = UpgradePlanner::new(&round_data, &game_data, &utility);
+
+    let options = planner.options();
+    assert_eq!(options[0].item, TradeItemType::Mining1);
+    assert_eq!(options.len(), 3);
+
+    let mut decision_info = GameDecisionInfo::new();
+    decision_info.robots.insert("r".to_string(), RobotDecisionInfo::new("r".to_string(), Box::new(NoneAction::new()), Box::new(NoneAction::new()), false));
+    let remaining = planner.plan(&mut decision_info, Money::from_amount(60));
+
+    // one upgrade per robot and round
+    assert_eq!(remaining, Money::from_amount(10));
+    assert!(decision_info.robots["r"].has_upgrade);
+  }
+
+  #[test]
+  fn test_saves_for_a_much_better_upgrade() {
+    let (mut round_data, game_data) = data();
+    round_data.item_prices.insert(TradeItemType::Mining1, Money::from_amount(100));
+    round_data.item_prices.insert(TradeItemType::Storage1, Money::from_amount(20));
+    let utility = UtilityModel::new(UtilityParameters::new(), &round_data, &game_data);
+    let planner = UpgradePlanner::new(&round_data, &game_data, &utility);
+
+    // iron triples the income, the bigger storage only saves a few selling rounds
+    assert_eq!(planner.savings_target(Money::from_amount(90)).map(|option| option.item), Some(TradeItemType::Mining1));
+    assert_eq!(planner.savings_target(Money::from_amount(100)), None);
+  }
+}
@@ -0,0 +1,313 @@
+use std::fmt;
+
+use crate::domainprimitives::location::mineable_resource::MineableResource;
+use crate::domainprimitives::location::mineable_resource_type::MineableResourceType;
+use crate::domainprimitives::purchasing::money::Money;
+use crate::domainprimitives::purchasing::trade_item_type::TradeItemType;
+use crate::game::application::combat_planner::FocusFire;
+use crate::game::application::lookahead_planner::Plan;
+use crate::game::domain::game_logic_info::{PersistentData, TransientData};
+use crate::robot::domain::energy_model::EnergyPlan;
+use crate::robot::domain::inventory_valuation::CargoValue;
+use crate::robot::domain::robot::{PersistentRobotInfo, TransientRobotInfo};
+
+/// The knobs of the utility scores, so a strategy can be tuned without touching the formulas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UtilityParameters {
+  pub risk_aversion: f32, // how strongly danger above what the robot can handle eats into the income
+  pub route_commitment: f32, // factor on the income of the target a robot picked in an earlier round
+  pub exploration_share: f32, // share of an average deposit we expect to get out of exploring a planet
+  pub sell_fill_exponent: f32, // the higher, the longer robots keep mining before they sell a half full storage
+  pub kill_bounty: f32, // what destroying an enemy robot is worth to us
+  pub energy_price: f32, // money per energy point as long as there is no energy restore price
+  pub health_price: f32, // money per health point as long as there is no health restore price
+}
+
+impl UtilityParameters {
+  pub fn new() -> Self {
+    Self {
+      risk_aversion: 1.,
+      route_commitment: 1.25,
+      exploration_share: 0.2,
+      sell_fill_exponent: 2.,
+      kill_bounty: 50.,
+      energy_price: 1.,
+      health_price: 5.,
+    }
+  }
+}
+
+/// Expected value of an action in money, split up by where it comes from, and the rounds the robot is busy with it.
+/// Comparing `per_round` puts mining, travelling, exploring, selling and fighting on one scale.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UtilityScore {
+  terms: Vec<(&'static str, f32)>,
+  rounds: u32,
+}
+
+impl UtilityScore {
+  pub fn new() -> Self {
+    Self {
+      terms: Vec::new(),
+      rounds: 0,
+    }
+  }
+
+  pub fn with(mut self, name: &'static str, value: f32) -> Self {
+    self.terms.push((name, value));
+    self
+  }
+
+  pub fn over(mut self, rounds: u32) -> Self {
+    self.rounds += rounds;
+    self
+  }
+
+  pub fn value(&self) -> f32 {
+    self.terms.iter().map(|(_, value)| value).sum()
+  }
+
+  pub fn rounds(&self) -> u32 {
+    self.rounds.max(1)
+  }
+
+  pub fn per_round(&self) -> f32 {
+    self.value() / self.rounds() as f32
+  }
+}
+
+impl fmt::Display for UtilityScore {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{:.2}/round =", self.per_round())?;
+    for (name, value) in &self.terms {
+      write!(f, " {} {:+.2}", name, value)?;
+    }
+    write!(f, " over {} rounds", self.rounds())
+  }
+}
+
+/// Scores the candidate actions of a robot from the live prices, the rounds it takes, the energy spent and the danger on the way.
+pub struct UtilityModel<'a> {
+  parameters: UtilityParameters,
+  round_data: &'a TransientData,
+  game_data: &'a PersistentData,
+}
+
+impl<'a> UtilityModel<'a> {
+  pub fn new(parameters: UtilityParameters, round_data: &'a TransientData, game_data: &'a PersistentData) -> Self {
+    Self {
+      parameters,
+      round_data,
+      game_data,
+    }
+  }
+
+  fn resource_price(&self, resource_type: MineableResourceType) -> f32 {
+    self.round_data.resource_price(resource_type)
+  }
+
+  // what the resource is expected to sell for `rounds` rounds from now, following its price trend
+  fn expected_resource_price(&self, resource_type: MineableResourceType, rounds: u32) -> f32 {
+    self.game_data.price_history.expected_resource_price(resource_type, self.resource_price(resource_type), rounds)
+  }
+
+  // what a restore costs per point it refills, a restore always fills up to the maximum
+  fn restore_price_per_point(&self, item: TradeItemType, max_points: u16, fallback: f32) -> f32 {
+    match self.round_data.item_prices.get(&item) {
+      Some(price) if !price.is_zero() && max_points > 0 => price.to_f32() / max_points as f32,
+      _ => fallback,
+    }
+  }
+
+  /// Money one energy point of the robot is worth.
+  pub fn energy_price(&self, robot: &TransientRobotInfo) -> f32 {
+    self.restore_price_per_point(TradeItemType::EnergyRestore, robot.energy_level.get_max_energy_value_for_level(), self.parameters.energy_price)
+  }
+
+  /// Money one health point of the robot is worth.
+  pub fn health_price(&self, robot: &TransientRobotInfo) -> f32 {
+    self.restore_price_per_point(TradeItemType::HealthRestore, robot.health_level.get_max_health_value_for_level(), self.parameters.health_price)
+  }
+
+  /// Filling the free storage with the resource, or whatever is left of it, sold at the price expected once the storage is full.
+  /// None if there is nothing to earn.
+  pub fn mine(&self, robot_info: &PersistentRobotInfo, robot: &TransientRobotInfo, planet_id: &str, resource: MineableResource) -> Option<UtilityScore> {
+    let free_storage = robot_info.inventory.max_storage.saturating_sub(robot_info.inventory.used_storage) as u32;
+    let haul = free_storage.min(resource.current_amount);
+    let mining_speed = (robot.mining_speed_level.get_mining_speed_value_for_level() as u32).max(1);
+    let income = haul as f32 * self.expected_resource_price(resource.resource_type, haul.div_ceil(mining_speed));
+    if income <= 0. {
+      return None;
+    }
+
+    let excess_danger = self.game_data.danger_map.excess_danger(planet_id, robot_info.attack_damage);
+    let risk = income * (1. - 1. / (1. + self.parameters.risk_aversion * excess_danger));
+    let mut score = UtilityScore::new()
+      .with("income", income)
+      .with("risk", -risk)
+      .over(haul.div_ceil(mining_speed));
+
+    if robot_info.travel_target.as_deref() == Some(planet_id) {
+      score = score.with("commitment", (income - risk) * (self.parameters.route_commitment - 1.));
+    }
+    Some(score)
+  }
+
+  /// Some of what we expect from an average deposit, `expected_value` already accounts for other robots exploring nearby.
+  pub fn explore(&self, expected_value: f32) -> UtilityScore {
+    UtilityScore::new()
+      .with("discovery", expected_value * self.parameters.exploration_share)
+  }
+
+  /// Travelling before the task, regenerating on the way or - with `restore` - buying an energy restore right away.
+  pub fn travel(&self, score: UtilityScore, robot: &TransientRobotInfo, hops: u32, plan: &EnergyPlan, restore: bool) -> UtilityScore {
+    let score = score.with("energy", -(plan.route_cost as f32) * self.energy_price(robot));
+    if restore {
+      let restore_price = self.round_data.item_prices.get(&TradeItemType::EnergyRestore).copied().map_or(0., Money::to_f32);
+      score.with("restore", -restore_price).over(hops + plan.rounds_with_restore)
+    } else {
+      score.over(hops + plan.regeneration_rounds)
+    }
+  }
+
+  /// Selling the whole cargo, the fuller the storage the more it pays to spend a round on it. While the prices climb
+  /// it pays to hold the cargo until the storage is full, while they fall to sell early - and right away if the robot may not survive the next round.
+  pub fn sell(&self, cargo: &CargoValue) -> UtilityScore {
+    let (value, value_when_full) = (cargo.value.to_f32(), cargo.value_when_full.to_f32());
+    let sell_now = value * cargo.fill.powf(self.parameters.sell_fill_exponent);
+    let trend = if value_when_full > 0. { sell_now * (value / value_when_full - 1.) } else { 0. };
+
+    UtilityScore::new()
+      .with("inventory", sell_now)
+      .with("trend", trend)
+      .with("risk", (value - sell_now) * cargo.loss_risk)
+      .over(1)
+  }
+
+  /// Selling the whole cargo for what it is worth, when there is no time left to fill up the storage.
+  pub fn sell_out(&self, cargo: &CargoValue) -> UtilityScore {
+    UtilityScore::new()
+      .with("inventory", cargo.value.to_f32())
+      .over(1)
+  }
+
+  /// Attacking the focus target until it is destroyed: our share of the bounty and of the mining the enemy no longer
+  /// takes from us, paying for the damage the enemies deal back in the meantime.
+  pub fn attack(&self, robot: &TransientRobotInfo, focus_fire: &FocusFire) -> UtilityScore {
+    UtilityScore::new()
+      .with("bounty", self.parameters.kill_bounty * focus_fire.kill_share)
+      .with("loot", focus_fire.loot * focus_fire.kill_share)
+      .with("damage", -(focus_fire.damage_taken as f32) * self.health_price(robot))
+      .over(focus_fire.rounds)
+  }
+
+  /// A plan over the next rounds as the forward model played it through, sticking to the plan of last round earns the commitment bonus.
+  pub fn plan(&self, plan: &Plan) -> UtilityScore {
+    let mut score = UtilityScore::new()
+      .with("plan", plan.value)
+      .over(plan.steps.len() as u32);
+    if plan.committed {
+      score = score.with("commitment", plan.value * (self.parameters.route_commitment - 1.));
+    }
+    score
+  }
+
+  /// The energy a round of regeneration gives back.
+  pub fn regenerate(&self, robot: &TransientRobotInfo) -> UtilityScore {
+    let missing = robot.energy_level.get_max_energy_value_for_level().saturating_sub(robot.energy);
+    let regenerated = missing.min(robot.energy_regen_level.get_energy_regen_value_for_level());
+
+    UtilityScore::new()
+      .with("energy", regenerated as f32 * self.energy_price(robot))
+      .over(1)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::robot::domain::inventory_valuation::InventoryValuation;
+  use crate::robot::domain::robot::Inventory;
+
+  use super::*;
+
+  fn robot(energy: u16) -> (PersistentRobotInfo, TransientRobotInfo) {
+    let robot_info = PersistentRobotInfo::fresh("r", Inventory::new(5, 0, 0, 0, 0, false, 5, 20));
+    let robot = TransientRobotInfo::level0("r", "p", energy, 10);
+    (robot_info, robot)
+  }
+
+  #[test]
+  fn test_mining_is_scored_per_round_with_breakdown() {
+    let mut round_data = TransientData::new();
+    let mut game_data = PersistentData::new();
+    round_data.resource_prices.insert(MineableResourceType::COAL, Money::from_amount(5));
+    game_data.danger_map.record_enemy("p", 1);
+    let model = UtilityModel::new(UtilityParameters::new(), &round_data, &game_data);
+    let (robot_info, robot) = robot(20);
+
+    let coal = MineableResource::from_type_amount_and_max_amount(MineableResourceType::COAL, 100, 100);
+    let score = model.mine(&robot_info, &robot, "p", coal).unwrap();
+
+    // 15 free storage at 2 per round, the enemy we saw costs half the income
+    assert_eq!(score.value(), 37.5);
+    assert_eq!(score.to_string(), "4.69/round = income +75.00 risk -37.50 over 8 rounds");
+  }
+
+  #[test]
+  fn test_actions_compete_on_one_scale() {
+    let mut round_data = TransientData::new();
+    let game_data = PersistentData::new();
+    round_data.resource_prices.insert(MineableResourceType::COAL, Money::from_amount(5));
+    round_data.item_prices.insert(TradeItemType::EnergyRestore, Money::from_amount(40));
+    let model = UtilityModel::new(UtilityParameters::new(), &round_data, &game_data);
+    let (robot_info, robot) = robot(12);
+
+    // 5 coal in a quarter of the storage are not worth a round yet
+    let cargo = InventoryValuation::new(&round_data, &game_data).appraise(&robot_info, Some(&robot));
+    assert_eq!(model.sell(&cargo).per_round(), 25. * 0.25 * 0.25);
+    // 4 energy at 2 per point
+    assert_eq!(model.regenerate(&robot).per_round(), 8.);
+
+    let coal = MineableResource::from_type_amount_and_max_amount(MineableResourceType::COAL, 100, 100);
+    let plan = EnergyPlan { next_hop_cost: 2, route_cost: 4, regeneration_rounds: 0, rounds_with_restore: 1 };
+    let travelled = model.travel(model.mine(&robot_info, &robot, "p", coal).unwrap(), &robot, 2, &plan, false);
+    assert_eq!(travelled.per_round(), (75. - 8.) / 10.);
+  }
+
+  #[test]
+  fn test_climbing_prices_hold_the_inventory_and_pay_for_mining() {
+    let mut round_data = TransientData::new();
+    let mut game_data = PersistentData::new();
+    round_data.resource_prices.insert(MineableResourceType::COAL, Money::from_amount(5));
+    for (round, price) in [(1, 3), (2, 4), (3, 5)] {
+      game_data.price_history.record_resource(MineableResourceType::COAL, round, Money::from_amount(price));
+    }
+    let model = UtilityModel::new(UtilityParameters::new(), &round_data, &game_data);
+    let (robot_info, robot) = robot(20);
+
+    // filling the storage takes 8 rounds, by then coal is expected at 7.50 instead of 5
+    let cargo = InventoryValuation::new(&round_data, &game_data).appraise(&robot_info, Some(&robot));
+    assert_eq!(cargo.rounds_to_fill, 8);
+    assert_eq!(model.sell(&cargo).per_round(), 25. * 0.25 * 0.25 * (5. / 7.5));
+    let coal = MineableResource::from_type_amount_and_max_amount(MineableResourceType::COAL, 100, 100);
+    assert_eq!(model.mine(&robot_info, &robot, "p", coal).unwrap().value(), 15. * 7.5);
+    // selling out takes the price of today
+    assert_eq!(model.sell_out(&cargo).value(), 25.);
+  }
+
+  #[test]
+  fn test_cargo_at_risk_is_sold_early() {
+    let mut round_data = TransientData::new();
+    let mut game_data = PersistentData::new();
+    round_data.resource_prices.insert(MineableResourceType::COAL, Money::from_amount(5));
+    game_data.danger_map.record_attack("p", 2);
+    let model = UtilityModel::new(UtilityParameters::new(), &round_data, &game_data);
+    let (robot_info, robot) = robot(12);
+    let cargo = InventoryValuation::new(&round_data, &game_data).appraise(&robot_info, Some(&robot));
+
+    // 4 danger against 10 health: the cargo held back to fill up the storage is lost with a 40% chance
+    let sell_now = 25. * 0.25 * 0.25;
+    assert_eq!(model.sell(&cargo).value(), sell_now + (25. - sell_now) * 0.4);
+    assert!(model.sell(&cargo).per_round() > model.regenerate(&robot).per_round());
+  }
+}
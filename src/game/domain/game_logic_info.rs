@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-use crate::{domainprimitives::{location::mineable_resource_type::MineableResourceType, purchasing::trade_item_type::TradeItemType}, eventinfrastructure::robot::{self, dto::robot_resource_inventory_dto}, planet::domain::planet::{PersistentPlanetInfo, TransientPlanetInfo}, robot::domain::robot::{PersistentRobotInfo, RobotDecisionInfo, TransientRobotInfo}};
+use crate::domainprimitives::purchasing::money::Money;
+use crate::game::domain::game_phase::GamePhase;
+
+use crate::{domainprimitives::{location::mineable_resource_type::MineableResourceType, purchasing::trade_item_type::TradeItemType}, eventinfrastructure::robot::{self, dto::robot_resource_inventory_dto}, player::domain::enemy_profile::EnemyProfiles, planet::domain::{danger_map::DangerMap, mining_history::MiningHistory, planet::{PersistentPlanetInfo, TransientPlanetInfo}, world_map::WorldMap}, robot::domain::{combat_log::CombatLog, energy_model::EnergyModel, robot::{PersistentRobotInfo, RobotDecisionInfo, TransientRobotInfo}}, transaction::domain::{ledger::Ledger, price_history::PriceHistory, reconciliation::Reconciliation}};
 
 pub struct GameDecisionInfo {
   pub robots: HashMap<String, RobotDecisionInfo>
@@ -19,9 +22,9 @@ pub struct TransientData {
   pub robots: HashMap<String, TransientRobotInfo>,
   pub enemy_robots: HashMap<String, TransientRobotInfo>,
   pub planets: HashMap<String, TransientPlanetInfo>,
-  pub balance: f32,
-  pub item_prices: HashMap<TradeItemType, f32>,
-  pub resource_prices: HashMap<MineableResourceType, f32>,
+  pub balance: Money,
+  pub item_prices: HashMap<TradeItemType, Money>,
+  pub resource_prices: HashMap<MineableResourceType, Money>,
 }
 
 impl TransientData {
@@ -29,7 +32,7 @@ impl TransientData {
     let robots = HashMap::new();
     let enemy_robots = HashMap::new();
     let planets = HashMap::new();
-    let balance = 0.;
+    let balance = Money::zero();
     let item_prices = HashMap::new();
     let resource_prices = HashMap::new();
 
@@ -42,26 +45,73 @@ impl TransientData {
       resource_prices,
     }
   }
+
+  /// The current price of the resource for scoring, 0 as long as we don't know it.
+  pub fn resource_price(&self, resource_type: MineableResourceType) -> f32 {
+    self.resource_prices.get(&resource_type).copied().map_or(0., Money::to_f32)
+  }
 }
 
 pub struct PersistentData {
   pub planets: HashMap<String, PersistentPlanetInfo>,
+  pub world_map: WorldMap,
+  pub mining_history: MiningHistory,
+  pub danger_map: DangerMap,
+  pub combat_log: CombatLog,
+  pub enemy_profiles: EnemyProfiles,
+  pub energy_model: EnergyModel,
+  pub price_history: PriceHistory,
+  pub ledger: Ledger,
+  pub reconciliation: Reconciliation,
   pub robots: HashMap<String, PersistentRobotInfo>,
   pub player_id: String,
   pub robot_buy_amount: u16,
+  pub current_round: u16,
+  pub max_rounds: u16, // 0 as long as we don't know how long the game goes
 }
 
 impl PersistentData {
   pub fn new() -> Self {
     let planets = HashMap::new();
+    let world_map = WorldMap::new();
+    let mining_history = MiningHistory::new();
+    let danger_map = DangerMap::new();
+    let combat_log = CombatLog::new();
+    let enemy_profiles = EnemyProfiles::new();
+    let energy_model = EnergyModel::new();
+    let price_history = PriceHistory::new();
+    let ledger = Ledger::new();
+    let reconciliation = Reconciliation::new();
     let robots = HashMap::new();
     let player_id = String::new();
     let robot_buy_amount = 0;
+    let current_round = 0;
+    let max_rounds = 0;
     Self {
       planets,
+      world_map,
+      mining_history,
+      danger_map,
+      combat_log,
+      enemy_profiles,
+      energy_model,
+      price_history,
+      ledger,
+      reconciliation,
       robots,
       player_id,
       robot_buy_amount,
+      current_round,
+      max_rounds,
     }
   }
+
+  pub fn game_phase(&self) -> GamePhase {
+    GamePhase::from_progress(self.current_round, self.max_rounds)
+  }
+
+  /// Rounds left after the current one, None as long as we don't know how long the game goes.
+  pub fn remaining_rounds(&self) -> Option<u16> {
+    (self.max_rounds > 0).then(|| self.max_rounds.saturating_sub(self.current_round))
+  }
 }
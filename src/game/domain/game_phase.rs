@@ -0,0 +1,43 @@
+// share of the game the opening takes
+const OPENING_SHARE: f32 = 0.2;
+// share of the game after which nothing new pays back anymore
+const ENDGAME_SHARE: f32 = 0.85;
+
+/// How far the game has progressed, decides what is still worth buying and doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamePhase {
+  Opening,
+  Midgame,
+  Endgame, // no more purchases, the inventory is sold before the last round
+}
+
+impl GamePhase {
+  /// As long as we don't know how long the game goes (`max_rounds` 0) it never ends.
+  pub fn from_progress(current_round: u16, max_rounds: u16) -> Self {
+    if max_rounds == 0 {
+      return if current_round == 0 { GamePhase::Opening } else { GamePhase::Midgame };
+    }
+
+    let progress = current_round as f32 / max_rounds as f32;
+    if progress >= ENDGAME_SHARE {
+      GamePhase::Endgame
+    } else if progress < OPENING_SHARE {
+      GamePhase::Opening
+    } else {
+      GamePhase::Midgame
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_phase_from_progress() {
+    assert_eq!(GamePhase::from_progress(5, 100), GamePhase::Opening);
+    assert_eq!(GamePhase::from_progress(50, 100), GamePhase::Midgame);
+    assert_eq!(GamePhase::from_progress(85, 100), GamePhase::Endgame);
+    assert_eq!(GamePhase::from_progress(500, 0), GamePhase::Midgame);
+  }
+}
@@ -1,3 +1,4 @@
 pub mod game;
 pub mod game_status;
 pub mod game_logic_info;
+pub mod game_phase;
@@ -19,7 +19,7 @@ impl ResourceMinedEventHandler {
 #[async_trait]
 impl EventHandler<PlanetResourceMinedEvent> for ResourceMinedEventHandler {
   async fn handle(&self, event: PlanetResourceMinedEvent) {
-    self.game.lock().await.update_planet(event.planet, event.mined_amount);
+    self.game.lock().await.update_planet(event.planet, event.mined_amount, event.resource.current_amount);
   }
 }
 
@@ -56,8 +56,32 @@ impl EventHandler<PlanetDiscoveredEvent> for PlanetDiscoveredEventHandler {
         }
       }
 
-      let planet_info = PersistentPlanetInfo::new(event.planet.clone(), event.movement_difficulty, event.resource, north_planet, east_planet, south_planet, west_planet);
+      let planet_info = PersistentPlanetInfo::new(event.planet.clone(), event.movement_difficulty, event.resource, north_planet, east_planet, west_planet, south_planet);
 
       self.game.lock().await.save_planet(planet, planet_info);
   }
 }
+
+#[cfg(test)]
+mod test {
+  use crate::eventinfrastructure::map::dto::planet_neighbour_dto::PlanetNeighbourDto;
+
+  use super::*;
+
+  #[tokio::test]
+  async fn test_neighbours_are_saved_in_their_direction() {
+    let game = Arc::new(Mutex::new(GameLogicService::new()));
+    let neighbours = [("n", CompassDirection::NORTH), ("e", CompassDirection::EAST), ("s", CompassDirection::SOUTH), ("w", CompassDirection::WEST)]
+      .into_iter()
+      .map(|(planet_id, compass_direction)| PlanetNeighbourDto { planet_id: planet_id.to_string(), compass_direction })
+      .collect();
+    let event = PlanetDiscoveredEvent { planet: "p".to_string(), movement_difficulty: 1, neighbours, resource: None };
+
+    PlanetDiscoveredEventHandler::new(game.clone()).handle(event).await;
+
+    // PersistentPlanetInfo::new takes west before south
+    let game = game.lock().await;
+    let planet_info = &game.game_data.planets["p"];
+    assert_eq!((planet_info.north.as_str(), planet_info.east.as_str(), planet_info.south.as_str(), planet_info.west.as_str()), ("n", "e", "s", "w"));
+  }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+// share of the danger that is still remembered one round later
+const DANGER_DECAY: f32 = 0.6;
+// below this a planet is considered safe again and forgotten
+const DANGER_THRESHOLD: f32 = 0.5;
+// an enemy robot is a threat even before it upgraded its damage
+const ENEMY_PRESENCE_DANGER: f32 = 1.;
+// being attacked somewhere counts more than just seeing an enemy there
+const ATTACK_DANGER_FACTOR: f32 = 2.;
+
+/// Danger score per planet, measured in enemy attack damage, that fades away when nothing happens.
+pub struct DangerMap {
+  scores: HashMap<String, f32>,
+}
+
+impl DangerMap {
+  pub fn new() -> Self {
+    Self {
+      scores: HashMap::new(),
+    }
+  }
+
+  pub fn decay(&mut self) {
+    self.scores.retain(|_, score| {
+      *score *= DANGER_DECAY;
+      *score >= DANGER_THRESHOLD
+    });
+  }
+
+  pub fn record_enemy(&mut self, planet_id: &str, attack_damage: u16) {
+    *self.scores.entry(planet_id.to_string()).or_insert(0.) += ENEMY_PRESENCE_DANGER + attack_damage as f32;
+  }
+
+  pub fn record_attack(&mut self, planet_id: &str, damage_taken: u16) {
+    *self.scores.entry(planet_id.to_string()).or_insert(0.) += ATTACK_DANGER_FACTOR * damage_taken as f32;
+  }
+
+  pub fn danger_at(&self, planet_id: &str) -> f32 {
+    *self.scores.get(planet_id).unwrap_or(&0.)
+  }
+
+  /// How much more dangerous the planet is than the robot can handle, 0 if it can defend itself.
+  pub fn excess_danger(&self, planet_id: &str, own_attack_damage: u16) -> f32 {
+    (self.danger_at(planet_id) - own_attack_damage as f32).max(0.)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_danger_accumulates_and_decays() {
+    let mut danger_map = DangerMap::new();
+    danger_map.record_enemy("p", 4);
+    danger_map.record_attack("p", 5);
+    assert_eq!(danger_map.danger_at("p"), 15.);
+
+    danger_map.decay();
+    assert_eq!(danger_map.danger_at("p"), 9.);
+
+    for _ in 0..10 {
+      danger_map.decay();
+    }
+    assert_eq!(danger_map.danger_at("p"), 0.);
+  }
+
+  #[test]
+  fn test_strong_robots_ignore_weak_enemies() {
+    let mut danger_map = DangerMap::new();
+    danger_map.record_enemy("p", 4);
+
+    assert_eq!(danger_map.excess_danger("p", 10), 0.);
+    assert_eq!(danger_map.excess_danger("p", 1), 4.);
+    assert_eq!(danger_map.excess_danger("unknown", 0), 0.);
+  }
+}
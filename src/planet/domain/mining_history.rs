@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+// only the most recent rounds tell us how busy a deposit is right now
+const OBSERVATION_WINDOW: u16 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Extraction {
+  round: u16,
+  total: u32,
+  by_us: u32,
+}
+
+struct DepositHistory {
+  extractions: Vec<Extraction>,
+}
+
+impl DepositHistory {
+  // the events of a round arrive in any order, rounds that left the window are dropped
+  fn extraction_in_round(&mut self, round: u16) -> &mut Extraction {
+    self.extractions.retain(|e| e.round.saturating_add(OBSERVATION_WINDOW) > round);
+    let index = match self.extractions.iter().position(|e| e.round == round) {
+      Some(index) => index,
+      None => {
+        self.extractions.push(Extraction { round, total: 0, by_us: 0 });
+        self.extractions.len() - 1
+      }
+    };
+    &mut self.extractions[index]
+  }
+}
+
+/// Tracks how fast every deposit is mined, by us and by everybody else, to forecast when it runs out.
+pub struct MiningHistory {
+  deposits: HashMap<String, DepositHistory>,
+}
+
+impl MiningHistory {
+  pub fn new() -> Self {
+    Self {
+      deposits: HashMap::new(),
+    }
+  }
+
+  /// Everything mined on the planet, as reported by the map service.
+  pub fn record_extraction(&mut self, planet_id: &str, round: u16, mined_amount: u32) {
+    self.deposit(planet_id).extraction_in_round(round).total += mined_amount;
+  }
+
+  /// The part of the extraction our own robots are responsible for, the robot events may arrive before the planet event.
+  pub fn record_own_extraction(&mut self, planet_id: &str, round: u16, mined_amount: u32) {
+    self.deposit(planet_id).extraction_in_round(round).by_us += mined_amount;
+  }
+
+  /// Average amount mined per round over the last few rounds, split into (ours, enemies).
+  pub fn extraction_rates(&self, planet_id: &str, current_round: u16) -> (f32, f32) {
+    let Some(deposit) = self.deposits.get(planet_id) else {
+      return (0., 0.);
+    };
+    let window_start = current_round.saturating_sub(OBSERVATION_WINDOW - 1);
+
+    let (total, by_us) = deposit.extractions.iter()
+      .filter(|e| e.round >= window_start && e.round <= current_round)
+      .fold((0, 0), |(total, by_us), e| (total + e.total, by_us + e.by_us.min(e.total)));
+    let rounds = (current_round - window_start + 1) as f32;
+
+    (by_us as f32 / rounds, (total - by_us) as f32 / rounds)
+  }
+
+  /// Rounds until the deposit is empty at the current pace, None if nobody is mining it.
+  pub fn rounds_until_empty(&self, planet_id: &str, remaining: u32, current_round: u16) -> Option<u32> {
+    let (own_rate, enemy_rate) = self.extraction_rates(planet_id, current_round);
+    let rate = own_rate + enemy_rate;
+    if rate <= 0. {
+      return None;
+    }
+    Some((remaining as f32 / rate).ceil() as u32)
+  }
+
+  pub fn is_depleted_before(&self, planet_id: &str, remaining: u32, current_round: u16, arrival_in_rounds: u32) -> bool {
+    if remaining == 0 {
+      return true;
+    }
+    self.rounds_until_empty(planet_id, remaining, current_round).is_some_and(|rounds| rounds <= arrival_in_rounds)
+  }
+
+  fn deposit(&mut self, planet_id: &str) -> &mut DepositHistory {
+    self.deposits.entry(planet_id.to_string()).or_insert(DepositHistory {
+      extractions: Vec::new(),
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_extraction_rates_split_ours_and_enemies() {
+    let mut history = MiningHistory::new();
+    history.record_extraction("p", 1, 10);
+    history.record_own_extraction("p", 1, 4);
+    history.record_extraction("p", 2, 10);
+
+    let (own_rate, enemy_rate) = history.extraction_rates("p", 5);
+    assert_eq!(own_rate, 4. / 5.);
+    assert_eq!(enemy_rate, 16. / 5.);
+  }
+
+  #[test]
+  fn test_old_extractions_fall_out_of_the_window() {
+    let mut history = MiningHistory::new();
+    history.record_extraction("p", 1, 50);
+
+    assert_eq!(history.rounds_until_empty("p", 50, 5), Some(5));
+    assert_eq!(history.rounds_until_empty("p", 50, 6), None);
+  }
+
+  #[test]
+  fn test_events_out_of_order_and_old_rounds_are_dropped() {
+    let mut history = MiningHistory::new();
+    history.record_own_extraction("p", 2, 4);
+    history.record_extraction("p", 3, 10);
+    history.record_extraction("p", 2, 10);
+    assert_eq!(history.deposits["p"].extractions.len(), 2);
+    assert_eq!(history.extraction_rates("p", 6), (4. / 5., 16. / 5.));
+
+    for round in 4..100 {
+      history.record_extraction("p", round, 1);
+    }
+    assert_eq!(history.deposits["p"].extractions.len(), OBSERVATION_WINDOW as usize);
+  }
+
+  #[test]
+  fn test_deposit_depleted_before_arrival() {
+    let mut history = MiningHistory::new();
+    history.record_extraction("p", 3, 20);
+    history.record_extraction("p", 4, 20);
+
+    // 40 per 5 rounds -> 10 left are gone in 2 rounds
+    assert!(history.is_depleted_before("p", 10, 4, 3));
+    assert!(!history.is_depleted_before("p", 10, 4, 1));
+    assert!(!history.is_depleted_before("untouched", 10, 4, 100));
+  }
+}
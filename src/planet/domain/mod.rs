@@ -1 +1,5 @@
+pub mod danger_map;
+pub mod mining_history;
 pub mod planet;
+pub mod planet_graph;
+pub mod world_map;
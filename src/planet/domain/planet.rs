@@ -38,4 +38,12 @@ impl PersistentPlanetInfo {
       south,
     }
   }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+impl PersistentPlanetInfo {
+  /// An easy planet without resources, the neighbours in the order of the compass and "" where there is none.
+  pub fn with_neighbours(id: &str, north: &str, east: &str, south: &str, west: &str) -> Self {
+    Self::new(id.to_string(), 1, None, north.to_string(), east.to_string(), west.to_string(), south.to_string())
+  }
+}
@@ -0,0 +1,177 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::planet::domain::planet::PersistentPlanetInfo;
+
+// we only know the movement difficulty of a planet once it has been discovered
+const UNKNOWN_MOVEMENT_DIFFICULTY: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+  pub hops: Vec<String>, // planet ids after the start planet, the last one is the target
+  pub cost: u32,
+}
+
+pub struct ShortestPaths {
+  start: String,
+  costs: HashMap<String, u32>,
+  previous: HashMap<String, String>,
+}
+
+impl ShortestPaths {
+  pub fn cost_to(&self, planet_id: &str) -> Option<u32> {
+    self.costs.get(planet_id).copied()
+  }
+
+  pub fn route_to(&self, planet_id: &str) -> Option<Route> {
+    let cost = self.cost_to(planet_id)?;
+    let mut hops = Vec::new();
+    let mut current = planet_id.to_string();
+
+    while current != self.start {
+      let previous = self.previous.get(&current)?.clone();
+      hops.push(current);
+      current = previous;
+    }
+    hops.reverse();
+
+    Some(Route {
+      hops,
+      cost,
+    })
+  }
+}
+
+pub struct PlanetGraph {
+  edges: HashMap<String, Vec<(String, u32)>>,
+}
+
+impl PlanetGraph {
+  pub fn new(planets: &HashMap<String, PersistentPlanetInfo>) -> Self {
+    let mut edges = HashMap::new();
+
+    for (id, planet) in planets {
+      let mut neighbours = Vec::new();
+      for neighbour_id in [&planet.north, &planet.east, &planet.south, &planet.west] {
+        if neighbour_id.is_empty() {
+          continue;
+        }
+        let cost = match planets.get(neighbour_id) {
+          Some(neighbour) => (neighbour.movement_difficulty as u32).max(1),
+          None => UNKNOWN_MOVEMENT_DIFFICULTY,
+        };
+        neighbours.push((neighbour_id.clone(), cost));
+      }
+      edges.insert(id.clone(), neighbours);
+    }
+
+    Self {
+      edges,
+    }
+  }
+
+  pub fn neighbours(&self, planet_id: &str) -> &[(String, u32)] {
+    match self.edges.get(planet_id) {
+      Some(neighbours) => neighbours,
+      None => &[],
+    }
+  }
+
+  /// Dijkstra from `start` over every planet we know of. Undiscovered neighbours are reachable, but we can't path through them.
+  pub fn shortest_paths_from(&self, start: &str) -> ShortestPaths {
+    self.shortest_paths_with_penalty_from(start, |_| 0)
+  }
+
+  /// Same as `shortest_paths_from`, entering a planet additionally costs its `penalty`, e.g. for danger.
+  pub fn shortest_paths_with_penalty_from(&self, start: &str, penalty: impl Fn(&str) -> u32) -> ShortestPaths {
+    let mut costs: HashMap<String, u32> = HashMap::new();
+    let mut previous: HashMap<String, String> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+
+    costs.insert(start.to_string(), 0);
+    queue.push(Reverse((0, start.to_string())));
+
+    while let Some(Reverse((cost, planet_id))) = queue.pop() {
+      if costs.get(&planet_id).is_some_and(|c| cost > *c) {
+        continue;
+      }
+
+      for (neighbour_id, edge_cost) in self.neighbours(&planet_id) {
+        let next_cost = cost + edge_cost + penalty(neighbour_id);
+        if costs.get(neighbour_id).is_none_or(|c| next_cost < *c) {
+          costs.insert(neighbour_id.clone(), next_cost);
+          previous.insert(neighbour_id.clone(), planet_id.clone());
+          queue.push(Reverse((next_cost, neighbour_id.clone())));
+        }
+      }
+    }
+
+    ShortestPaths {
+      start: start.to_string(),
+      costs,
+      previous,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  // a - b - c
+  // |       |
+  // d ----- e   (d is hard to cross)
+  fn planets() -> HashMap<String, PersistentPlanetInfo> {
+    let mut planets = HashMap::new();
+    planets.insert("a".to_string(), PersistentPlanetInfo::with_neighbours("a", "", "b", "d", ""));
+    planets.insert("b".to_string(), PersistentPlanetInfo::with_neighbours("b", "", "c", "", "a"));
+    planets.insert("c".to_string(), PersistentPlanetInfo { movement_difficulty: 2, ..PersistentPlanetInfo::with_neighbours("c", "", "", "e", "b") });
+    planets.insert("d".to_string(), PersistentPlanetInfo { movement_difficulty: 4, ..PersistentPlanetInfo::with_neighbours("d", "a", "e", "", "") });
+    planets.insert("e".to_string(), PersistentPlanetInfo::with_neighbours("e", "c", "x", "", "d"));
+    planets
+  }
+
+  #[test]
+  fn test_shortest_path_prefers_low_difficulty() {
+    let graph = PlanetGraph::new(&planets());
+    let route = graph.shortest_paths_from("a").route_to("e").unwrap();
+
+    assert_eq!(route.hops, vec!["b".to_string(), "c".to_string(), "e".to_string()]);
+    assert_eq!(route.cost, 4);
+  }
+
+  #[test]
+  fn test_shortest_path_to_start_is_empty() {
+    let graph = PlanetGraph::new(&planets());
+    let route = graph.shortest_paths_from("a").route_to("a").unwrap();
+
+    assert!(route.hops.is_empty());
+    assert_eq!(route.cost, 0);
+  }
+
+  #[test]
+  fn test_undiscovered_neighbour_is_reachable() {
+    let graph = PlanetGraph::new(&planets());
+    let route = graph.shortest_paths_from("a").route_to("x").unwrap();
+
+    assert_eq!(route.hops.last(), Some(&"x".to_string()));
+    assert_eq!(route.cost, 4 + UNKNOWN_MOVEMENT_DIFFICULTY);
+    assert!(graph.neighbours("x").is_empty());
+  }
+
+  #[test]
+  fn test_penalty_routes_around_planet() {
+    let graph = PlanetGraph::new(&planets());
+    let route = graph.shortest_paths_with_penalty_from("a", |id| if id == "c" { 10 } else { 0 }).route_to("e").unwrap();
+
+    assert_eq!(route.hops, vec!["d".to_string(), "e".to_string()]);
+    assert_eq!(route.cost, 5);
+  }
+
+  #[test]
+  fn test_unreachable_planet() {
+    let graph = PlanetGraph::new(&planets());
+
+    assert_eq!(graph.shortest_paths_from("a").route_to("unknown"), None);
+  }
+}
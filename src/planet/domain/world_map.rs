@@ -0,0 +1,289 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::domainprimitives::location::compass_direction_dto::CompassDirection;
+use crate::domainprimitives::location::grid_position::GridPosition;
+use crate::planet::domain::planet::PersistentPlanetInfo;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapInconsistency {
+  // the planet was reached through two neighbour chains that disagree on where it is
+  ConflictingPosition { planet_id: String, known: GridPosition, derived: GridPosition },
+  // two different planets claim the same cell
+  OccupiedCell { planet_id: String, occupant_id: String, position: GridPosition },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+  pub min: GridPosition,
+  pub max: GridPosition,
+}
+
+impl BoundingBox {
+  pub fn width(&self) -> u32 {
+    self.max.x.abs_diff(self.min.x) + 1
+  }
+
+  pub fn height(&self) -> u32 {
+    self.max.y.abs_diff(self.min.y) + 1
+  }
+}
+
+// a connected part of the map with its own coordinate system
+struct MapFragment {
+  positions: HashMap<String, GridPosition>,
+  cells: HashMap<GridPosition, String>,
+}
+
+impl MapFragment {
+  fn new() -> Self {
+    Self {
+      positions: HashMap::new(),
+      cells: HashMap::new(),
+    }
+  }
+}
+
+/// Assigns (x, y) coordinates to every planet we know of, discovered or only seen as a neighbour.
+/// The fragment containing the first spawn planet is the main one, its coordinates never move.
+pub struct WorldMap {
+  fragments: HashMap<usize, MapFragment>,
+  fragment_of: HashMap<String, usize>,
+  discovered: HashSet<String>,
+  main_fragment: Option<usize>,
+  next_fragment_id: usize,
+  pub inconsistencies: Vec<MapInconsistency>,
+}
+
+impl WorldMap {
+  pub fn new() -> Self {
+    Self {
+      fragments: HashMap::new(),
+      fragment_of: HashMap::new(),
+      discovered: HashSet::new(),
+      main_fragment: None,
+      next_fragment_id: 0,
+      inconsistencies: Vec::new(),
+    }
+  }
+
+  pub fn anchor(&mut self, planet_id: &str) {
+    let fragment_id = match self.fragment_of.get(planet_id) {
+      Some(fragment_id) => *fragment_id,
+      None => self.new_fragment(planet_id),
+    };
+
+    if self.main_fragment.is_none() {
+      self.main_fragment = Some(fragment_id);
+    }
+  }
+
+  /// Places the planet and its neighbours, merging fragments that turn out to be connected. Returns the inconsistencies this planet caused.
+  pub fn add_planet(&mut self, planet: &PersistentPlanetInfo) -> Vec<MapInconsistency> {
+    let known_inconsistencies = self.inconsistencies.len();
+    let neighbours: Vec<(CompassDirection, &String)> = vec![
+      (CompassDirection::NORTH, &planet.north),
+      (CompassDirection::EAST, &planet.east),
+      (CompassDirection::SOUTH, &planet.south),
+      (CompassDirection::WEST, &planet.west),
+    ];
+
+    self.discovered.insert(planet.id.clone());
+
+    if !self.fragment_of.contains_key(&planet.id) {
+      let mut derived = None;
+      for (direction, neighbour_id) in &neighbours {
+        if let Some((fragment_id, neighbour_position)) = self.position_in_fragment(neighbour_id) {
+          derived = Some((fragment_id, neighbour_position.neighbour(&direction.get_opposite_direction())));
+          break;
+        }
+      }
+
+      match derived {
+        Some((fragment_id, position)) => {
+          self.place(fragment_id, &planet.id, position);
+        }
+        None => {
+          self.new_fragment(&planet.id);
+        }
+      }
+    }
+
+    for (direction, neighbour_id) in &neighbours {
+      if neighbour_id.is_empty() {
+        continue;
+      }
+
+      // merging may have moved the planet itself, so look it up again for every neighbour
+      let Some((fragment_id, position)) = self.position_in_fragment(&planet.id) else {
+        break;
+      };
+      let derived = position.neighbour(direction);
+
+      match self.position_in_fragment(neighbour_id) {
+        None => {
+          self.place(fragment_id, neighbour_id, derived);
+        }
+        Some((neighbour_fragment_id, known)) if neighbour_fragment_id == fragment_id => {
+          if known != derived {
+            self.inconsistencies.push(MapInconsistency::ConflictingPosition {
+              planet_id: neighbour_id.to_string(),
+              known,
+              derived,
+            });
+          }
+        }
+        Some((neighbour_fragment_id, known)) => {
+          self.merge(fragment_id, neighbour_fragment_id, derived.x - known.x, derived.y - known.y);
+        }
+      }
+    }
+
+    self.inconsistencies[known_inconsistencies..].to_vec()
+  }
+
+  /// Position in the stable coordinate system of the main fragment.
+  pub fn position_of(&self, planet_id: &str) -> Option<GridPosition> {
+    let (fragment_id, position) = self.position_in_fragment(planet_id)?;
+    if Some(fragment_id) == self.main_fragment {
+      Some(position)
+    } else {
+      None
+    }
+  }
+
+  pub fn fragment_count(&self) -> usize {
+    self.fragments.len()
+  }
+
+  pub fn bounding_box(&self) -> Option<BoundingBox> {
+    let fragment = self.fragments.get(&self.main_fragment?)?;
+    let mut positions = fragment.positions.values();
+    let first = *positions.next()?;
+
+    Some(positions.fold(BoundingBox { min: first, max: first }, |bounds, position| BoundingBox {
+      min: GridPosition::new(bounds.min.x.min(position.x), bounds.min.y.min(position.y)),
+      max: GridPosition::new(bounds.max.x.max(position.x), bounds.max.y.max(position.y)),
+    }))
+  }
+
+  /// Closest planet that we only know as someone's neighbour, measured on the grid.
+  pub fn nearest_unexplored_cell(&self, from_planet_id: &str) -> Option<(String, GridPosition)> {
+    let (fragment_id, from) = self.position_in_fragment(from_planet_id)?;
+
+    self.fragments.get(&fragment_id)?.positions.iter()
+      .filter(|(planet_id, _)| !self.discovered.contains(*planet_id))
+      .min_by_key(|(planet_id, position)| (position.manhattan_distance(&from), (*planet_id).clone()))
+      .map(|(planet_id, position)| (planet_id.clone(), *position))
+  }
+
+  fn position_in_fragment(&self, planet_id: &str) -> Option<(usize, GridPosition)> {
+    let fragment_id = *self.fragment_of.get(planet_id)?;
+    let position = *self.fragments.get(&fragment_id)?.positions.get(planet_id)?;
+    Some((fragment_id, position))
+  }
+
+  fn new_fragment(&mut self, planet_id: &str) -> usize {
+    let fragment_id = self.next_fragment_id;
+    self.next_fragment_id += 1;
+    self.fragments.insert(fragment_id, MapFragment::new());
+    self.place(fragment_id, planet_id, GridPosition::origin());
+    fragment_id
+  }
+
+  fn place(&mut self, fragment_id: usize, planet_id: &str, position: GridPosition) -> bool {
+    if let Some(fragment) = self.fragments.get_mut(&fragment_id) {
+      if let Some(occupant_id) = fragment.cells.get(&position) {
+        if occupant_id != planet_id {
+          self.inconsistencies.push(MapInconsistency::OccupiedCell {
+            planet_id: planet_id.to_string(),
+            occupant_id: occupant_id.clone(),
+            position,
+          });
+          return false;
+        }
+        return true;
+      }
+
+      fragment.positions.insert(planet_id.to_string(), position);
+      fragment.cells.insert(position, planet_id.to_string());
+      self.fragment_of.insert(planet_id.to_string(), fragment_id);
+      return true;
+    }
+    false
+  }
+
+  // the offset translates coordinates of `other_id` into the frame of `fragment_id`
+  fn merge(&mut self, fragment_id: usize, other_id: usize, x_offset: i32, y_offset: i32) {
+    let (target_id, source_id, x_offset, y_offset) = if self.main_fragment == Some(other_id) {
+      (other_id, fragment_id, -x_offset, -y_offset)
+    } else {
+      (fragment_id, other_id, x_offset, y_offset)
+    };
+
+    if let Some(source) = self.fragments.remove(&source_id) {
+      for (planet_id, position) in source.positions {
+        self.fragment_of.remove(&planet_id);
+        self.place(target_id, &planet_id, position.translate(x_offset, y_offset));
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_positions_relative_to_spawn_planet() {
+    let mut world_map = WorldMap::new();
+    world_map.anchor("spawn");
+    world_map.add_planet(&PersistentPlanetInfo::with_neighbours("spawn", "n", "e", "", ""));
+    world_map.add_planet(&PersistentPlanetInfo::with_neighbours("e", "", "ee", "", "spawn"));
+
+    assert_eq!(world_map.position_of("spawn"), Some(GridPosition::origin()));
+    assert_eq!(world_map.position_of("n"), Some(GridPosition::new(0, -1)));
+    assert_eq!(world_map.position_of("ee"), Some(GridPosition::new(2, 0)));
+    assert_eq!(world_map.bounding_box(), Some(BoundingBox { min: GridPosition::new(0, -1), max: GridPosition::new(2, 0) }));
+  }
+
+  #[test]
+  fn test_fragments_merge_into_main_fragment() {
+    let mut world_map = WorldMap::new();
+    world_map.anchor("spawn");
+    world_map.add_planet(&PersistentPlanetInfo::with_neighbours("spawn", "", "a", "", ""));
+    world_map.add_planet(&PersistentPlanetInfo::with_neighbours("far", "", "", "", "c"));
+    assert_eq!(world_map.fragment_count(), 2);
+    assert_eq!(world_map.position_of("far"), None);
+
+    // b connects a and c: spawn - a - b - c - far
+    world_map.add_planet(&PersistentPlanetInfo::with_neighbours("b", "", "c", "", "a"));
+
+    assert_eq!(world_map.fragment_count(), 1);
+    assert_eq!(world_map.position_of("far"), Some(GridPosition::new(4, 0)));
+    assert!(world_map.inconsistencies.is_empty());
+  }
+
+  #[test]
+  fn test_inconsistent_offsets_are_flagged() {
+    let mut world_map = WorldMap::new();
+    world_map.anchor("spawn");
+    world_map.add_planet(&PersistentPlanetInfo::with_neighbours("spawn", "", "a", "", ""));
+    let inconsistencies = world_map.add_planet(&PersistentPlanetInfo::with_neighbours("a", "spawn", "", "", ""));
+
+    assert_eq!(inconsistencies, vec![MapInconsistency::ConflictingPosition {
+      planet_id: "spawn".to_string(),
+      known: GridPosition::origin(),
+      derived: GridPosition::new(1, -1),
+    }]);
+  }
+
+  #[test]
+  fn test_nearest_unexplored_cell() {
+    let mut world_map = WorldMap::new();
+    world_map.anchor("spawn");
+    world_map.add_planet(&PersistentPlanetInfo::with_neighbours("spawn", "", "a", "", ""));
+    world_map.add_planet(&PersistentPlanetInfo::with_neighbours("a", "", "b", "", "spawn"));
+
+    assert_eq!(world_map.nearest_unexplored_cell("spawn"), Some(("b".to_string(), GridPosition::new(2, 0))));
+  }
+}
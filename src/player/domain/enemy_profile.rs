@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::robot::domain::robot::TransientRobotInfo;
+
+// damage per round a player has to deal to us to count as aggressive
+const AGGRESSIVE_DAMAGE_PER_ROUND: f32 = 1.;
+// extra hops a route through the territory of an aggressive player counts as
+const TERRITORY_PENALTY: u32 = 3;
+// fleet sizes remembered per player
+const FLEET_HISTORY_LENGTH: usize = 50;
+
+/// What we know about one enemy player from the robots of theirs we have seen.
+pub struct EnemyProfile {
+    robots: HashMap<String, TransientRobotInfo>, // alive as far as we know
+    fleet_sizes: Vec<(u16, usize)>, // (round, robots)
+    sightings: HashMap<String, u32>, // planet id -> times one of their robots was seen there mining
+    damage_to_us: u32,
+    first_seen: u16,
+}
+
+impl EnemyProfile {
+    fn new(first_seen: u16) -> Self {
+        Self {
+            robots: HashMap::new(),
+            fleet_sizes: Vec::new(),
+            sightings: HashMap::new(),
+            damage_to_us: 0,
+            first_seen,
+        }
+    }
+
+    pub fn fleet_size(&self) -> usize {
+        self.robots.len()
+    }
+
+    /// Fleet size `rounds` rounds ago, or as long ago as we know the player.
+    pub fn fleet_size_before(&self, rounds: u16) -> usize {
+        let Some((latest_round, _)) = self.fleet_sizes.last() else {
+            return self.fleet_size();
+        };
+        let round = latest_round.saturating_sub(rounds);
+        self.fleet_sizes.iter()
+            .find(|(r, _)| *r >= round)
+            .map(|(_, size)| *size)
+            .unwrap_or(self.fleet_size())
+    }
+
+    /// Number of robots per level from 0 to 5, a robot's level is the average of its upgrade levels.
+    pub fn level_distribution(&self) -> [usize; 6] {
+        let mut distribution = [0; 6];
+        for robot in self.robots.values() {
+            let levels = [robot.health_level, robot.damage_level, robot.mining_speed_level, robot.mining_level, robot.energy_level, robot.energy_regen_level, robot.storage_level];
+            let level = levels.iter().map(|level| *level as usize).sum::<usize>() / levels.len();
+            distribution[level.min(5)] += 1;
+        }
+        distribution
+    }
+
+    /// Planets they mine on, most frequently seen first.
+    pub fn mining_planets(&self) -> Vec<&str> {
+        let mut planets: Vec<(&str, u32)> = self.sightings.iter().map(|(planet_id, count)| (planet_id.as_str(), *count)).collect();
+        planets.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        planets.into_iter().map(|(planet_id, _)| planet_id).collect()
+    }
+
+    /// Damage they dealt to our robots per round since we first saw them.
+    pub fn aggression(&self, current_round: u16) -> f32 {
+        self.damage_to_us as f32 / (current_round.saturating_sub(self.first_seen) + 1) as f32
+    }
+
+    pub fn is_aggressive(&self, current_round: u16) -> bool {
+        self.aggression(current_round) >= AGGRESSIVE_DAMAGE_PER_ROUND
+    }
+}
+
+/// Profiles of the enemy players, built from the revealed robots grouped by their owner and the attacks on us.
+pub struct EnemyProfiles {
+    profiles: HashMap<String, EnemyProfile>, // player notion -> profile
+    owners: HashMap<String, String>, // robot id -> player notion
+}
+
+impl EnemyProfiles {
+    pub fn new() -> Self {
+        Self {
+            profiles: HashMap::new(),
+            owners: HashMap::new(),
+        }
+    }
+
+    /// `mining` tells whether the robot was seen on a deposit it can mine.
+    pub fn record_robot(&mut self, player: &str, robot: &TransientRobotInfo, mining: bool, round: u16) {
+        let profile = self.profiles.entry(player.to_string()).or_insert_with(|| EnemyProfile::new(round));
+        profile.robots.insert(robot.id.clone(), robot.clone());
+        if mining {
+            *profile.sightings.entry(robot.planet_id.clone()).or_insert(0) += 1;
+        }
+        self.owners.insert(robot.id.clone(), player.to_string());
+    }
+
+    pub fn record_destroyed(&mut self, robot_id: &str) {
+        if let Some(profile) = self.owners.get(robot_id).and_then(|player| self.profiles.get_mut(player)) {
+            profile.robots.remove(robot_id);
+        }
+    }
+
+    pub fn record_attack_on_us(&mut self, attacker_id: &str, damage_taken: u16) {
+        if let Some(profile) = self.owners.get(attacker_id).and_then(|player| self.profiles.get_mut(player)) {
+            profile.damage_to_us += damage_taken as u32;
+        }
+    }
+
+    /// Remembers the fleet size of every player at the end of the round.
+    pub fn end_round(&mut self, round: u16) {
+        for profile in self.profiles.values_mut() {
+            profile.fleet_sizes.push((round, profile.robots.len()));
+            if profile.fleet_sizes.len() > FLEET_HISTORY_LENGTH {
+                profile.fleet_sizes.remove(0);
+            }
+        }
+    }
+
+    pub fn owner_of(&self, robot_id: &str) -> Option<&str> {
+        self.owners.get(robot_id).map(|player| player.as_str())
+    }
+
+    pub fn profile(&self, player: &str) -> Option<&EnemyProfile> {
+        self.profiles.get(player)
+    }
+
+    pub fn players(&self) -> Vec<&str> {
+        let mut players: Vec<&str> = self.profiles.keys().map(|player| player.as_str()).collect();
+        players.sort();
+        players
+    }
+
+    /// Extra hops for a route through the planet, if an aggressive player mines there.
+    pub fn territory_penalty(&self, planet_id: &str, current_round: u16) -> u32 {
+        let aggressive_territory = self.profiles.values()
+            .any(|profile| profile.is_aggressive(current_round) && profile.sightings.contains_key(planet_id));
+        if aggressive_territory { TERRITORY_PENALTY } else { 0 }
+    }
+}
+
+impl fmt::Display for EnemyProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} robots (was {} ten rounds ago), levels {:?}, mines mostly on {:?}, {} damage dealt to us",
+            self.fleet_size(),
+            self.fleet_size_before(10),
+            self.level_distribution(),
+            self.mining_planets().into_iter().take(3).collect::<Vec<&str>>(),
+            self.damage_to_us
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domainprimitives::purchasing::robot_level::RobotLevel;
+
+    use super::*;
+
+    fn robot(id: &str, planet_id: &str, level: RobotLevel) -> TransientRobotInfo {
+        TransientRobotInfo::new(id.to_string(), planet_id.to_string(), 20, 10, level, level, level, level, level, level, level)
+    }
+
+    #[test]
+    fn test_robots_are_grouped_by_player() {
+        let mut profiles = EnemyProfiles::new();
+        profiles.record_robot("a", &robot("a1", "p", RobotLevel::LEVEL0), true, 1);
+        profiles.end_round(1);
+        profiles.record_robot("a", &robot("a2", "q", RobotLevel::LEVEL2), true, 2);
+        profiles.record_robot("a", &robot("a2", "q", RobotLevel::LEVEL2), true, 3);
+        profiles.record_robot("b", &robot("b1", "p", RobotLevel::LEVEL1), false, 3);
+        profiles.end_round(3);
+
+        let a = profiles.profile("a").unwrap();
+        assert_eq!(a.fleet_size(), 2);
+        assert_eq!(a.fleet_size_before(2), 1);
+        assert_eq!(a.level_distribution(), [1, 0, 1, 0, 0, 0]);
+        assert_eq!(a.mining_planets(), vec!["q", "p"]);
+        assert_eq!(profiles.owner_of("b1"), Some("b"));
+        assert_eq!(profiles.players(), vec!["a", "b"]);
+
+        profiles.record_destroyed("a1");
+        assert_eq!(profiles.profile("a").unwrap().fleet_size(), 1);
+    }
+
+    #[test]
+    fn test_aggressive_players_territory_is_avoided() {
+        let mut profiles = EnemyProfiles::new();
+        profiles.record_robot("a", &robot("a1", "p", RobotLevel::LEVEL0), true, 1);
+        profiles.record_robot("b", &robot("b1", "q", RobotLevel::LEVEL0), true, 1);
+        profiles.record_attack_on_us("a1", 4);
+        profiles.record_attack_on_us("b1", 1);
+
+        // 4 damage in 4 rounds against 1
+        assert_eq!(profiles.profile("a").unwrap().aggression(4), 1.);
+        assert_eq!(profiles.territory_penalty("p", 4), TERRITORY_PENALTY);
+        assert_eq!(profiles.territory_penalty("q", 4), 0);
+    }
+}
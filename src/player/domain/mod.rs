@@ -1 +1,2 @@
+pub mod enemy_profile;
 pub mod player;
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use tokio::sync::Mutex;
 
-use crate::{eventinfrastructure::{event_handler::EventHandler, robot::{robot_moved_event::RobotMovedEvent, robot_regenerated_event::RobotRegeneratedEvent, robot_resource_mined_event::RobotResourceMinedEvent, robot_resource_removed_event::RobotResourceRemovedEvent, robot_spawned_event::RobotSpawnedEvent, robot_upgraded_event::RobotUpgradedEvent, robots_revealed_event::RobotsRevealedEvent}}, game::application::game_logic_service::GameLogicService, robot::domain::robot::{Inventory, Robot, TransientRobotInfo}};
+use crate::{eventinfrastructure::{event_handler::EventHandler, robot::{robot_attacked_event::RobotAttackedEvent, robot_moved_event::RobotMovedEvent, robot_regenerated_event::RobotRegeneratedEvent, robot_resource_mined_event::RobotResourceMinedEvent, robot_resource_removed_event::RobotResourceRemovedEvent, robot_spawned_event::RobotSpawnedEvent, robot_upgraded_event::RobotUpgradedEvent, robots_revealed_event::RobotsRevealedEvent}}, game::application::game_logic_service::GameLogicService, robot::domain::robot::{Inventory, Robot, TransientRobotInfo}};
 
 pub struct RobotsRevealedEventHandler {
   game: Arc<Mutex<GameLogicService>>, // this needs to be a pointer doesn't it?
@@ -30,14 +30,32 @@ impl EventHandler<RobotsRevealedEvent> for RobotsRevealedEventHandler {
         }
       }
       else {
-        if let Some(_) = game_mut.round_data.enemy_robots.get(&r.robot_id) {
-          game_mut.update_enemy_robot(&mut robot);
-        }
+        // enemies only ever show up here, so every revealed one is remembered
+        game_mut.update_enemy_robot(&r.player_notion, &mut robot);
       }
     }
   }
 }
 
+pub struct RobotAttackedEventHandler {
+  game: Arc<Mutex<GameLogicService>>,
+}
+
+impl RobotAttackedEventHandler {
+  pub fn new(game: Arc<Mutex<GameLogicService>>) -> Self {
+    Self {
+      game,
+    }
+  }
+}
+
+#[async_trait]
+impl EventHandler<RobotAttackedEvent> for RobotAttackedEventHandler {
+  async fn handle(&self, event: RobotAttackedEvent) {
+    self.game.lock().await.update_robot_attacked(event.attacker.robot_id, event.target.robot_id, event.target.available_health, event.target.alive);
+  }
+}
+
 pub struct RobotSpawnedEventHandler {
   game: Arc<Mutex<GameLogicService>>,
 }
@@ -118,7 +136,7 @@ impl RobotMovedEventHandler {
 #[async_trait]
 impl EventHandler<RobotMovedEvent> for RobotMovedEventHandler {
   async fn handle(&self, event: RobotMovedEvent) {
-    self.game.lock().await.update_robot_location(event.robot_id, event.to_planet.planet_id, event.remaining_energy);
+    self.game.lock().await.update_robot_location(event.robot_id, event.to_planet.planet_id, event.to_planet.movement_difficulty, event.remaining_energy);
   }
 }
 
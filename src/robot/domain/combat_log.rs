@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+// share of an enemy's hostility that is still remembered one round later
+const HOSTILITY_DECAY: f32 = 0.8;
+// below this the enemy is considered peaceful again and forgotten
+const HOSTILITY_THRESHOLD: f32 = 0.5;
+
+/// What the attack results told us: which enemy robots fight us and how many of theirs we destroyed.
+pub struct CombatLog {
+  hostility: HashMap<String, f32>, // enemy robot id -> damage it dealt to us, fading away
+  kills: u32,
+  losses: u32,
+}
+
+impl CombatLog {
+  pub fn new() -> Self {
+    Self {
+      hostility: HashMap::new(),
+      kills: 0,
+      losses: 0,
+    }
+  }
+
+  pub fn record_attack_on_us(&mut self, attacker_id: &str, damage_taken: u16, destroyed: bool) {
+    *self.hostility.entry(attacker_id.to_string()).or_insert(0.) += damage_taken as f32;
+    if destroyed {
+      self.losses += 1;
+    }
+  }
+
+  pub fn record_attack_by_us(&mut self, target_id: &str, destroyed: bool) {
+    if destroyed {
+      self.hostility.remove(target_id);
+      self.kills += 1;
+    }
+  }
+
+  pub fn decay(&mut self) {
+    self.hostility.retain(|_, damage| {
+      *damage *= HOSTILITY_DECAY;
+      *damage >= HOSTILITY_THRESHOLD
+    });
+  }
+
+  /// Recent damage the enemy robot dealt to us, 0 if it left us alone.
+  pub fn hostility_of(&self, enemy_id: &str) -> f32 {
+    *self.hostility.get(enemy_id).unwrap_or(&0.)
+  }
+
+  /// (enemy robots destroyed, own robots lost) so far.
+  pub fn score(&self) -> (u32, u32) {
+    (self.kills, self.losses)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_hostility_fades_and_ends_with_the_kill() {
+    let mut combat_log = CombatLog::new();
+    combat_log.record_attack_on_us("e", 5, false);
+    combat_log.record_attack_on_us("f", 2, true);
+    combat_log.decay();
+    assert_eq!(combat_log.hostility_of("e"), 4.);
+
+    combat_log.record_attack_by_us("e", true);
+    assert_eq!(combat_log.hostility_of("e"), 0.);
+    assert_eq!(combat_log.score(), (1, 1));
+  }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use crate::domainprimitives::purchasing::robot_level::RobotLevel;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnergyPlan {
+  pub next_hop_cost: u16,
+  pub route_cost: u32,
+  pub regeneration_rounds: u32, // rounds spent regenerating somewhere along the route
+  pub rounds_with_restore: u32, // rounds lost when buying an energy restore right now instead
+}
+
+/// Learns what moving onto a planet of a given movement difficulty costs from the energy our robots have left after moving.
+pub struct EnergyModel {
+  move_costs: HashMap<u8, (u32, u32)>, // difficulty -> (total energy spent, moves)
+}
+
+impl EnergyModel {
+  pub fn new() -> Self {
+    Self {
+      move_costs: HashMap::new(),
+    }
+  }
+
+  pub fn record_move(&mut self, movement_difficulty: u8, energy_before: u16, energy_after: u16) {
+    if energy_after >= energy_before {
+      return; // regenerated in between, we can't tell what the move cost
+    }
+    let (total, moves) = self.move_costs.entry(movement_difficulty).or_insert((0, 0));
+    *total += (energy_before - energy_after) as u32;
+    *moves += 1;
+  }
+
+  /// Observed average, until we have seen such a move we expect it to cost its difficulty.
+  pub fn move_cost(&self, movement_difficulty: u8) -> u16 {
+    match self.move_costs.get(&movement_difficulty) {
+      Some((total, moves)) if *moves > 0 => total.div_ceil(*moves) as u16,
+      _ => movement_difficulty.max(1) as u16,
+    }
+  }
+
+  // we don't know the difficulty of undiscovered planets, so expect the worst we have seen
+  fn unknown_move_cost(&self) -> u16 {
+    self.move_costs.keys().map(|difficulty| self.move_cost(*difficulty)).max().unwrap_or(1)
+  }
+
+  /// `hop_difficulties` are the movement difficulties of the planets along the route, None if not discovered yet.
+  pub fn plan_route(&self, energy: u16, energy_level: RobotLevel, energy_regen_level: RobotLevel, hop_difficulties: &[Option<u8>]) -> EnergyPlan {
+    let hop_costs: Vec<u16> = hop_difficulties.iter()
+      .map(|difficulty| match difficulty {
+        Some(difficulty) => self.move_cost(*difficulty),
+        None => self.unknown_move_cost(),
+      })
+      .collect();
+    let route_cost: u32 = hop_costs.iter().map(|cost| *cost as u32).sum();
+    let max_energy = energy_level.get_max_energy_value_for_level() as u32;
+    let energy_regen = (energy_regen_level.get_energy_regen_value_for_level() as u32).max(1);
+
+    EnergyPlan {
+      next_hop_cost: hop_costs.first().copied().unwrap_or(0),
+      route_cost,
+      regeneration_rounds: route_cost.saturating_sub(energy as u32).div_ceil(energy_regen),
+      rounds_with_restore: 1 + route_cost.saturating_sub(max_energy).div_ceil(energy_regen),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_move_cost_learned_from_observations() {
+    let mut energy_model = EnergyModel::new();
+    assert_eq!(energy_model.move_cost(2), 2);
+
+    energy_model.record_move(2, 20, 17);
+    energy_model.record_move(2, 17, 13);
+    energy_model.record_move(2, 5, 9);
+
+    assert_eq!(energy_model.move_cost(2), 4);
+    assert_eq!(energy_model.unknown_move_cost(), 4);
+  }
+
+  #[test]
+  fn test_plan_route_with_regeneration() {
+    let energy_model = EnergyModel::new();
+    let plan = energy_model.plan_route(3, RobotLevel::LEVEL0, RobotLevel::LEVEL0, &[Some(2), Some(3), Some(3), None]);
+
+    assert_eq!(plan.next_hop_cost, 2);
+    assert_eq!(plan.route_cost, 9);
+    // 6 energy missing at 4 per round
+    assert_eq!(plan.regeneration_rounds, 2);
+    assert_eq!(plan.rounds_with_restore, 1);
+  }
+}
@@ -0,0 +1,216 @@
+use crate::domainprimitives::location::mineable_resource_type::MineableResourceType;
+use crate::game::domain::game_logic_info::{PersistentData, TransientData};
+use crate::robot::domain::robot::{PersistentRobotInfo, TransientRobotInfo};
+
+// not sure what mining costs, expect a little
+const MINE_ENERGY_COST: u16 = 1;
+// share the price of a resource drops by for every unit we sell of it, a guess until we watch the prices
+const SELL_PRICE_IMPACT: f32 = 0.002;
+// selling never pushes a price below this share of what it is now
+const MIN_PRICE_SHARE: f32 = 0.5;
+// cargo still on board at the end of a plan has yet to be sold and is worth less than money
+const UNSOLD_CARGO_SHARE: f32 = 0.8;
+
+const RESOURCE_TYPES: [MineableResourceType; 5] = [MineableResourceType::COAL, MineableResourceType::IRON, MineableResourceType::GEM, MineableResourceType::GOLD, MineableResourceType::PLATIN];
+
+/// One round of a plan.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PlannedStep {
+  Move(String), // to a neighbouring planet
+  Mine,
+  Sell,
+  Regenerate,
+}
+
+/// Where a robot is and what it has after some rounds of a plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedRobot {
+  pub planet_id: String,
+  pub energy: u16,
+  pub cargo: [u32; 5], // per resource type, in the order of `MineableResourceType`
+  pub earned: f32, // money from selling
+  pub rounds: u32,
+  mined: Vec<(String, u32)>, // what the plan took out of each deposit
+  sold: [u32; 5],
+}
+
+impl SimulatedRobot {
+  pub fn used_storage(&self) -> u32 {
+    self.cargo.iter().sum()
+  }
+}
+
+/// Plays a robot's actions forward round by round: energy spent and regenerated, storage filling up, deposits running out
+/// under our and the enemies' mining, and prices following their trend and falling as we sell. The stats come from the robot's levels, the move costs
+/// and the enemy extraction rates from what we observed.
+pub struct ForwardModel<'a> {
+  round_data: &'a TransientData,
+  game_data: &'a PersistentData,
+  max_energy: u16,
+  energy_regen: u16,
+  mining_speed: u32,
+  max_storage: u32,
+  mining_level: u8,
+}
+
+impl<'a> ForwardModel<'a> {
+  pub fn new(round_data: &'a TransientData, game_data: &'a PersistentData, robot: &TransientRobotInfo) -> Self {
+    Self {
+      round_data,
+      game_data,
+      max_energy: robot.energy_level.get_max_energy_value_for_level(),
+      energy_regen: robot.energy_regen_level.get_energy_regen_value_for_level(),
+      mining_speed: robot.mining_speed_level.get_mining_speed_value_for_level() as u32,
+      max_storage: robot.storage_level.get_storage_value_for_level() as u32,
+      mining_level: robot.mining_level as u8,
+    }
+  }
+
+  pub fn start(&self, robot_info: &PersistentRobotInfo, robot: &TransientRobotInfo) -> SimulatedRobot {
+    let inventory = &robot_info.inventory;
+    SimulatedRobot {
+      planet_id: robot.planet_id.clone(),
+      energy: robot.energy,
+      cargo: [inventory.coal, inventory.iron, inventory.gem, inventory.gold, inventory.platin].map(u32::from),
+      earned: 0.,
+      rounds: 0,
+      mined: Vec::new(),
+      sold: [0; 5],
+    }
+  }
+
+  // the price `rounds` into the plan following its trend, after we sold `sold` units of it
+  fn price(&self, resource_type: MineableResourceType, rounds: u32, sold: u32) -> f32 {
+    let current_price = self.round_data.resource_price(resource_type);
+    let price = self.game_data.price_history.expected_resource_price(resource_type, current_price, rounds);
+    price * (1. - SELL_PRICE_IMPACT * sold as f32).max(MIN_PRICE_SHARE)
+  }
+
+  // what is left of the deposit on the planet by the time the robot gets to mine it
+  fn remaining(&self, state: &SimulatedRobot, planet_id: &str) -> u32 {
+    let Some(resource) = self.game_data.planets.get(planet_id).and_then(|planet| planet.resource) else {
+      return 0;
+    };
+    let (_, enemy_rate) = self.game_data.mining_history.extraction_rates(planet_id, self.game_data.current_round);
+    let taken_by_enemies = (enemy_rate * state.rounds as f32).ceil() as u32;
+    let taken_by_us: u32 = state.mined.iter().filter(|(id, _)| id == planet_id).map(|(_, amount)| amount).sum();
+    resource.current_amount.saturating_sub(taken_by_enemies + taken_by_us)
+  }
+
+  /// The steps the robot can take next, None where it lacks the energy, the storage or something to mine.
+  pub fn steps(&self, state: &SimulatedRobot) -> Vec<PlannedStep> {
+    let mut steps = vec![PlannedStep::Regenerate];
+    if state.used_storage() > 0 {
+      steps.push(PlannedStep::Sell);
+    }
+    let Some(planet) = self.game_data.planets.get(&state.planet_id) else {
+      return steps;
+    };
+
+    let mineable = planet.resource.is_some_and(|resource| self.mining_level >= resource.resource_type as u8);
+    if mineable && state.energy >= MINE_ENERGY_COST && state.used_storage() < self.max_storage && self.remaining(state, &planet.id) > 0 {
+      steps.push(PlannedStep::Mine);
+    }
+    for neighbour_id in [&planet.north, &planet.east, &planet.south, &planet.west] {
+      if let Some(neighbour) = self.game_data.planets.get(neighbour_id) {
+        if state.energy >= self.game_data.energy_model.move_cost(neighbour.movement_difficulty) {
+          steps.push(PlannedStep::Move(neighbour.id.clone()));
+        }
+      }
+    }
+    steps
+  }
+
+  /// The robot one round later, the step has to be one of `steps`.
+  pub fn apply(&self, state: &SimulatedRobot, step: &PlannedStep) -> SimulatedRobot {
+    let mut next = state.clone();
+    next.rounds += 1;
+
+    match step {
+      PlannedStep::Move(planet_id) => {
+        let difficulty = self.game_data.planets.get(planet_id).map(|planet| planet.movement_difficulty).unwrap_or(1);
+        next.energy = next.energy.saturating_sub(self.game_data.energy_model.move_cost(difficulty));
+        next.planet_id = planet_id.clone();
+      }
+      PlannedStep::Mine => {
+        if let Some(resource) = self.game_data.planets.get(&state.planet_id).and_then(|planet| planet.resource) {
+          let amount = self.mining_speed.min(self.max_storage.saturating_sub(state.used_storage())).min(self.remaining(state, &state.planet_id));
+          next.energy = next.energy.saturating_sub(MINE_ENERGY_COST);
+          next.cargo[resource.resource_type as usize] += amount;
+          next.mined.push((state.planet_id.clone(), amount));
+        }
+      }
+      PlannedStep::Sell => {
+        for resource_type in RESOURCE_TYPES {
+          let i = resource_type as usize;
+          let amount = next.cargo[i];
+          // the price drops while we sell, on average it is what it is halfway through
+          next.earned += amount as f32 * self.price(resource_type, state.rounds, next.sold[i] + amount / 2);
+          next.sold[i] += amount;
+          next.cargo[i] = 0;
+        }
+      }
+      PlannedStep::Regenerate => next.energy = (next.energy + self.energy_regen).min(self.max_energy),
+    }
+    next
+  }
+
+  /// What the robot has earned by the end of the plan, the cargo it still carries counted at a discount.
+  pub fn value(&self, state: &SimulatedRobot) -> f32 {
+    let cargo_value: f32 = RESOURCE_TYPES.iter()
+      .map(|resource_type| state.cargo[*resource_type as usize] as f32 * self.price(*resource_type, state.rounds, state.sold[*resource_type as usize]))
+      .sum();
+    state.earned + cargo_value * UNSOLD_CARGO_SHARE
+  }
+
+  /// Plays the steps from the start, None as soon as one of them is no longer possible.
+  pub fn simulate(&self, start: &SimulatedRobot, steps: &[PlannedStep]) -> Option<SimulatedRobot> {
+    let mut state = start.clone();
+    for step in steps {
+      if !self.steps(&state).contains(step) {
+        return None;
+      }
+      state = self.apply(&state, step);
+    }
+    Some(state)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::domainprimitives::location::mineable_resource::MineableResource;
+  use crate::domainprimitives::purchasing::money::Money;
+  use crate::planet::domain::planet::PersistentPlanetInfo;
+  use crate::robot::domain::robot::Inventory;
+
+  use super::*;
+
+  #[test]
+  fn test_mining_fills_the_storage_until_the_deposit_runs_out() {
+    let mut round_data = TransientData::new();
+    let mut game_data = PersistentData::new();
+    let coal = MineableResource::from_type_amount_and_max_amount(MineableResourceType::COAL, 5, 100);
+    game_data.planets.insert("p".to_string(), PersistentPlanetInfo::new("p".to_string(), 1, Some(coal), "q".to_string(), String::new(), String::new(), String::new()));
+    game_data.planets.insert("q".to_string(), PersistentPlanetInfo::new("q".to_string(), 3, None, String::new(), String::new(), String::new(), "p".to_string()));
+    round_data.resource_prices.insert(MineableResourceType::COAL, Money::from_amount(10));
+
+    let robot_info = PersistentRobotInfo::fresh("r", Inventory::new(0, 0, 0, 0, 0, false, 0, 20));
+    let robot = TransientRobotInfo::level0("r", "p", 2, 10);
+    let model = ForwardModel::new(&round_data, &game_data, &robot);
+    let start = model.start(&robot_info, &robot);
+
+    // 2 energy are not enough to move onto the difficulty 3 planet
+    assert_eq!(model.steps(&start), vec![PlannedStep::Regenerate, PlannedStep::Mine]);
+
+    let state = model.simulate(&start, &[PlannedStep::Mine, PlannedStep::Mine, PlannedStep::Regenerate, PlannedStep::Mine]).unwrap();
+    // 2 + 2 + the last 1 of the deposit
+    assert_eq!(state.cargo[0], 5);
+    assert_eq!(state.energy, 3);
+    assert!(!model.steps(&state).contains(&PlannedStep::Mine));
+    assert_eq!(model.value(&state), 5. * 10. * UNSOLD_CARGO_SHARE);
+
+    let sold = model.apply(&state, &PlannedStep::Sell);
+    assert_eq!(sold.earned, 5. * 10. * (1. - SELL_PRICE_IMPACT * 2.));
+    assert_eq!(sold.used_storage(), 0);
+  }
+}
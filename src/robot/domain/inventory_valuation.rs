@@ -0,0 +1,113 @@
+use std::fmt;
+
+use crate::domainprimitives::location::mineable_resource_type::MineableResourceType;
+use crate::domainprimitives::purchasing::money::Money;
+use crate::game::domain::game_logic_info::{PersistentData, TransientData};
+use crate::robot::domain::robot::{Inventory, PersistentRobotInfo, TransientRobotInfo};
+
+/// What a robot carries, priced now and at the prices expected once its storage is full, and how likely it is lost before it is sold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CargoValue {
+  pub value: Money, // at the current prices
+  pub value_when_full: Money,
+  pub fill: f32, // share of the storage used, 1 for a full robot
+  pub rounds_to_fill: u32,
+  pub loss_risk: f32, // chance the enemies around destroy the robot next round
+}
+
+/// Prices the five resource counters of an inventory with the current resource prices and their forecast.
+pub struct InventoryValuation<'a> {
+  round_data: &'a TransientData,
+  game_data: &'a PersistentData,
+}
+
+impl<'a> InventoryValuation<'a> {
+  pub fn new(round_data: &'a TransientData, game_data: &'a PersistentData) -> Self {
+    Self {
+      round_data,
+      game_data,
+    }
+  }
+
+  /// What the inventory is expected to sell for `rounds` rounds from now.
+  pub fn value(&self, inventory: &Inventory, rounds: u32) -> Money {
+    [
+      (MineableResourceType::COAL, inventory.coal),
+      (MineableResourceType::IRON, inventory.iron),
+      (MineableResourceType::GEM, inventory.gem),
+      (MineableResourceType::GOLD, inventory.gold),
+      (MineableResourceType::PLATIN, inventory.platin),
+    ].into_iter()
+      .map(|(resource_type, amount)| {
+        let price = self.game_data.price_history.expected_resource_price(resource_type, self.round_data.resource_price(resource_type), rounds);
+        Money::from_f32(price) * amount as u64
+      })
+      .sum()
+  }
+
+  /// The cargo of the robot, without its current state it is taken to be safe.
+  pub fn appraise(&self, robot_info: &PersistentRobotInfo, robot: Option<&TransientRobotInfo>) -> CargoValue {
+    let inventory = &robot_info.inventory;
+    let free_storage = inventory.max_storage.saturating_sub(inventory.used_storage) as u32;
+    let (fill, rounds_to_fill) = if inventory.full || free_storage == 0 {
+      (1., 0)
+    } else {
+      (inventory.used_storage as f32 / inventory.max_storage as f32, free_storage.div_ceil((robot_info.mining_speed as u32).max(1)))
+    };
+    let loss_risk = robot.map_or(0., |robot| match robot.health {
+      0 => 1.,
+      health => (self.game_data.danger_map.danger_at(&robot.planet_id) / health as f32).min(1.),
+    });
+
+    CargoValue {
+      value: self.value(inventory, 0),
+      value_when_full: self.value(inventory, rounds_to_fill),
+      fill,
+      rounds_to_fill,
+      loss_risk,
+    }
+  }
+}
+
+impl fmt::Display for CargoValue {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "worth {} ({} when full in {} rounds), {:.0}% full, {:.0}% risk to lose it",
+      self.value,
+      self.value_when_full,
+      self.rounds_to_fill,
+      self.fill * 100.,
+      self.loss_risk * 100.
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_prices_every_resource_and_the_risk_of_losing_it() {
+    let mut round_data = TransientData::new();
+    let mut game_data = PersistentData::new();
+    round_data.resource_prices.insert(MineableResourceType::COAL, Money::from_amount(5));
+    round_data.resource_prices.insert(MineableResourceType::GOLD, "12.5".parse().unwrap());
+    for (round, price) in [(1, 3), (2, 4), (3, 5)] {
+      game_data.price_history.record_resource(MineableResourceType::COAL, round, Money::from_amount(price));
+    }
+    game_data.danger_map.record_attack("p", 2);
+
+    let robot_info = PersistentRobotInfo::fresh("r", Inventory::new(4, 0, 2, 0, 0, false, 6, 20));
+    let robot = TransientRobotInfo::level0("r", "p", 20, 8);
+    let valuation = InventoryValuation::new(&round_data, &game_data);
+    let cargo = valuation.appraise(&robot_info, Some(&robot));
+
+    // 4 coal at 5 and 2 gold at 12.50, coal climbs by 1 per round for the 7 rounds it takes to fill the storage
+    assert_eq!(cargo.value, Money::from_amount(45));
+    assert_eq!(cargo.value_when_full, "7.50".parse::<Money>().unwrap() * 4 + Money::from_amount(25));
+    assert_eq!((cargo.fill, cargo.rounds_to_fill), (0.3, 7));
+    assert_eq!(cargo.loss_risk, game_data.danger_map.danger_at("p") / 8.);
+    assert_eq!(valuation.appraise(&robot_info, None).loss_risk, 0.);
+  }
+}
@@ -1 +1,6 @@
+pub mod combat_log;
+pub mod energy_model;
+pub mod forward_model;
+pub mod inventory_valuation;
 pub mod robot;
+pub mod robot_role;
@@ -1,5 +1,8 @@
 use crate::domainprimitives::command::action::Action;
 use crate::domainprimitives::purchasing::robot_level::RobotLevel;
+use crate::domainprimitives::purchasing::robot_upgrade_type::RobotUpgradeType;
+use crate::robot::domain::forward_model::PlannedStep;
+use crate::robot::domain::robot_role::RobotRole;
 use crate::repository::Identifiable;
 
 #[derive(Debug, Clone)]
@@ -59,6 +62,43 @@ impl TransientRobotInfo {
       storage_level,
     }
   }
+
+  pub fn level_for(&self, upgrade: &RobotUpgradeType) -> RobotLevel {
+    match upgrade {
+      RobotUpgradeType::Storage => self.storage_level,
+      RobotUpgradeType::Health => self.health_level,
+      RobotUpgradeType::Damage => self.damage_level,
+      RobotUpgradeType::MiningSpeed => self.mining_speed_level,
+      RobotUpgradeType::Mining => self.mining_level,
+      RobotUpgradeType::MaxEnergy => self.energy_level,
+      RobotUpgradeType::EnergyRegen => self.energy_regen_level,
+    }
+  }
+
+  pub fn set_level(&mut self, upgrade: &RobotUpgradeType, level: RobotLevel) {
+    match upgrade {
+      RobotUpgradeType::Storage => self.storage_level = level,
+      RobotUpgradeType::Health => self.health_level = level,
+      RobotUpgradeType::Damage => self.damage_level = level,
+      RobotUpgradeType::MiningSpeed => self.mining_speed_level = level,
+      RobotUpgradeType::Mining => self.mining_level = level,
+      RobotUpgradeType::MaxEnergy => self.energy_level = level,
+      RobotUpgradeType::EnergyRegen => self.energy_regen_level = level,
+    }
+  }
+}
+
+#[cfg(test)]
+impl TransientRobotInfo {
+  /// A robot without any upgrade, the one most tests start from.
+  pub fn level0(id: &str, planet_id: &str, energy: u16, health: u16) -> Self {
+    Self::new(id.to_string(), planet_id.to_string(), energy, health, RobotLevel::LEVEL0, RobotLevel::LEVEL0, RobotLevel::LEVEL0, RobotLevel::LEVEL0, RobotLevel::LEVEL0, RobotLevel::LEVEL0, RobotLevel::LEVEL0)
+  }
+
+  /// The same robot with another attack level, e.g. an enemy to fight.
+  pub fn with_damage(self, damage_level: RobotLevel) -> Self {
+    Self { damage_level, ..self }
+  }
 }
 
 impl Inventory {
@@ -85,12 +125,16 @@ pub struct PersistentRobotInfo {
   pub attack_damage: u16,
   pub mining_speed: u16,
   pub inventory: Inventory,
-  pub move_count: u16,   
+  pub travel_target: Option<String>,
+  pub plan: Vec<PlannedStep>, // the rest of the plan the robot is committed to
+  pub role: RobotRole,
 }
 
 impl PersistentRobotInfo {
   pub fn new(id: String, player_id: String, max_health: u16,max_energy: u16, energy_regen: u16, attack_damage: u16, mining_speed: u16, inventory: Inventory) -> Self {
-    let move_count = 0;
+    let travel_target = None;
+    let plan = Vec::new();
+    let role = RobotRole::Miner;
     Self {
       id,
       player_id,
@@ -100,11 +144,21 @@ impl PersistentRobotInfo {
       attack_damage,
       mining_speed,
       inventory,
-      move_count,
+      travel_target,
+      plan,
+      role,
     }
   }
 }
 
+#[cfg(test)]
+impl PersistentRobotInfo {
+  /// A robot of our player with the stats of a fresh one, carrying `inventory`.
+  pub fn fresh(id: &str, inventory: Inventory) -> Self {
+    Self::new(id.to_string(), "player".to_string(), 10, 20, 4, 1, 2, inventory)
+  }
+}
+
 pub struct RobotDecisionInfo {
   pub id: String,
   pub action: Box<dyn Action + Send + Sync>,
@@ -0,0 +1,41 @@
+use crate::domainprimitives::purchasing::robot_upgrade_type::RobotUpgradeType;
+
+/// What a robot is assigned to do, decides which tasks it takes and which of its stats are worth upgrading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RobotRole {
+  Miner,
+  Explorer, // a scout, never mines
+  Fighter, // mines only while there is nobody to fight
+}
+
+impl RobotRole {
+  pub fn mines(&self) -> bool {
+    *self != RobotRole::Explorer
+  }
+
+  pub fn explores(&self) -> bool {
+    *self != RobotRole::Fighter
+  }
+
+  pub fn fights(&self) -> bool {
+    *self != RobotRole::Explorer
+  }
+
+  /// The upgrades the role buys, all others are left alone.
+  pub fn upgrade_path(&self) -> Vec<RobotUpgradeType> {
+    match self {
+      RobotRole::Miner => vec![RobotUpgradeType::Mining, RobotUpgradeType::MiningSpeed, RobotUpgradeType::Storage, RobotUpgradeType::MaxEnergy, RobotUpgradeType::EnergyRegen],
+      RobotRole::Explorer => vec![RobotUpgradeType::MaxEnergy, RobotUpgradeType::EnergyRegen, RobotUpgradeType::Health],
+      RobotRole::Fighter => vec![RobotUpgradeType::Damage, RobotUpgradeType::Health, RobotUpgradeType::MaxEnergy, RobotUpgradeType::EnergyRegen],
+    }
+  }
+
+  /// Share of its time the role spends (mining, moving, fighting).
+  pub fn time_shares(&self) -> (f32, f32, f32) {
+    match self {
+      RobotRole::Miner => (1., 0.3, 0.1),
+      RobotRole::Explorer => (0., 1., 0.1),
+      RobotRole::Fighter => (0., 0.5, 1.),
+    }
+  }
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::domainprimitives::purchasing::money::{Money, Transfer};
+use crate::domainprimitives::purchasing::trade_item_type::ItemCategory;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+  Booking(Transfer), // the bank's view of a transaction, without the item
+  Purchase(ItemCategory),
+  Sale,
+}
+
+/// One booking, purchase or sale. Purchases take the total off the balance, sales add it, bookings tell by their transfer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerEntry {
+  pub round: u16,
+  pub kind: EntryKind,
+  pub item: String, // the trading name, empty for bookings
+  pub amount: u32,
+  pub unit_price: Money,
+  pub total: Money,
+  pub robot_id: Option<String>,
+}
+
+/// What one robot earned against what it cost.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RobotAccount {
+  pub earned: Money,
+  pub robot_price: Money,
+  pub upgrades: Money,
+  pub restores: Money,
+  upgrade_log: Vec<(String, Money, Money)>, // (item, price, earned before it was bought)
+}
+
+impl RobotAccount {
+  fn new() -> Self {
+    Self {
+      earned: Money::zero(),
+      robot_price: Money::zero(),
+      upgrades: Money::zero(),
+      restores: Money::zero(),
+      upgrade_log: Vec::new(),
+    }
+  }
+
+  pub fn profit(&self) -> Transfer {
+    Transfer::between(self.earned, self.robot_price + self.upgrades + self.restores)
+  }
+}
+
+/// What the upgrades of one kind cost and what the robots that got them earned since.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpgradeReturn {
+  pub item: String,
+  pub bought: u32,
+  pub spent: Money,
+  pub earned_since: Money,
+}
+
+/// Income, spending and what the bank booked so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LedgerTotals {
+  pub income: Money,
+  pub spending: Money,
+  pub credited: Money,
+  pub debited: Money,
+}
+
+/// Every booking, purchase and sale of the game, with the earnings of every robot set against what we spent on it
+/// and its upgrades, so we can see which robots and upgrades pay off.
+pub struct Ledger {
+  entries: Vec<LedgerEntry>,
+  accounts: HashMap<String, RobotAccount>,
+  unassigned_robot_prices: Vec<Money>, // robots bought but not spawned yet
+}
+
+impl Ledger {
+  pub fn new() -> Self {
+    Self {
+      entries: Vec::new(),
+      accounts: HashMap::new(),
+      unassigned_robot_prices: Vec::new(),
+    }
+  }
+
+  pub fn record(&mut self, entry: LedgerEntry) {
+    match (entry.kind, &entry.robot_id) {
+      (EntryKind::Purchase(ItemCategory::Robot), _) => {
+        self.unassigned_robot_prices.extend(std::iter::repeat_n(entry.unit_price, entry.amount as usize));
+      }
+      (EntryKind::Purchase(ItemCategory::Upgrade), Some(robot_id)) => {
+        let account = self.accounts.entry(robot_id.clone()).or_insert_with(RobotAccount::new);
+        account.upgrades += entry.total;
+        account.upgrade_log.push((entry.item.clone(), entry.total, account.earned));
+      }
+      (EntryKind::Purchase(_), Some(robot_id)) => {
+        self.accounts.entry(robot_id.clone()).or_insert_with(RobotAccount::new).restores += entry.total;
+      }
+      (EntryKind::Sale, Some(robot_id)) => {
+        self.accounts.entry(robot_id.clone()).or_insert_with(RobotAccount::new).earned += entry.total;
+      }
+      _ => {}
+    }
+    self.entries.push(entry);
+  }
+
+  /// A robot of ours spawned, it was paid for by the oldest robot purchase not assigned yet - the first robots come for free.
+  pub fn assign_robot(&mut self, robot_id: &str) {
+    let account = self.accounts.entry(robot_id.to_string()).or_insert_with(RobotAccount::new);
+    if !self.unassigned_robot_prices.is_empty() {
+      account.robot_price = self.unassigned_robot_prices.remove(0);
+    }
+  }
+
+  pub fn entries(&self) -> &[LedgerEntry] {
+    &self.entries
+  }
+
+  pub fn account(&self, robot_id: &str) -> Option<&RobotAccount> {
+    self.accounts.get(robot_id)
+  }
+
+  /// Robot ids with an account, the most profitable first.
+  pub fn robots_by_profit(&self) -> Vec<&str> {
+    let mut robots: Vec<(&str, Transfer)> = self.accounts.iter().map(|(id, account)| (id.as_str(), account.profit())).collect();
+    robots.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    robots.into_iter().map(|(id, _)| id).collect()
+  }
+
+  /// Returns per upgrade item, sorted by name.
+  pub fn upgrade_returns(&self) -> Vec<UpgradeReturn> {
+    let mut returns: HashMap<&str, UpgradeReturn> = HashMap::new();
+    for account in self.accounts.values() {
+      for (item, price, earned_before) in &account.upgrade_log {
+        let upgrade_return = returns.entry(item.as_str()).or_insert_with(|| UpgradeReturn { item: item.clone(), bought: 0, spent: Money::zero(), earned_since: Money::zero() });
+        upgrade_return.bought += 1;
+        upgrade_return.spent += *price;
+        upgrade_return.earned_since += account.earned.saturating_sub(earned_before);
+      }
+    }
+    let mut returns: Vec<UpgradeReturn> = returns.into_values().collect();
+    returns.sort_by(|a, b| a.item.cmp(&b.item));
+    returns
+  }
+
+  pub fn totals(&self) -> LedgerTotals {
+    let mut totals = LedgerTotals { income: Money::zero(), spending: Money::zero(), credited: Money::zero(), debited: Money::zero() };
+    for entry in &self.entries {
+      match entry.kind {
+        EntryKind::Booking(Transfer::Credit(_)) => totals.credited += entry.total,
+        EntryKind::Booking(Transfer::Debit(_)) => totals.debited += entry.total,
+        EntryKind::Purchase(_) => totals.spending += entry.total,
+        EntryKind::Sale => totals.income += entry.total,
+      }
+    }
+    totals
+  }
+}
+
+impl fmt::Display for RobotAccount {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "earned {} against robot {}, upgrades {}, restores {}: {}", self.earned, self.robot_price, self.upgrades, self.restores, self.profit())
+  }
+}
+
+impl fmt::Display for UpgradeReturn {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} bought {} times for {}, the robots earned {} since", self.item, self.bought, self.spent, self.earned_since)
+  }
+}
+
+impl fmt::Display for LedgerTotals {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "income {}, spending {}, booked +{} -{}", self.income, self.spending, self.credited, self.debited)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn entry(round: u16, kind: EntryKind, item: &str, amount: u32, unit_price: u64, robot_id: Option<&str>) -> LedgerEntry {
+    let unit_price = Money::from_amount(unit_price);
+    LedgerEntry { round, kind, item: item.to_string(), amount, unit_price, total: unit_price * amount as u64, robot_id: robot_id.map(str::to_string) }
+  }
+
+  #[test]
+  fn test_earnings_are_set_against_the_robot_and_its_upgrades() {
+    let mut ledger = Ledger::new();
+    ledger.assign_robot("a");
+    ledger.record(entry(1, EntryKind::Purchase(ItemCategory::Robot), "ROBOT", 2, 100, None));
+    ledger.assign_robot("b");
+    ledger.record(entry(2, EntryKind::Sale, "COAL", 10, 5, Some("a")));
+    ledger.record(entry(3, EntryKind::Purchase(ItemCategory::Upgrade), "MINING_SPEED_1", 1, 30, Some("a")));
+    ledger.record(entry(5, EntryKind::Sale, "COAL", 20, 5, Some("a")));
+    ledger.record(entry(5, EntryKind::Purchase(ItemCategory::Restore), "HEALTH_RESTORE", 1, 20, Some("b")));
+
+    let a = ledger.account("a").unwrap();
+    assert_eq!((a.earned, a.robot_price, a.upgrades), (Money::from_amount(150), Money::zero(), Money::from_amount(30)));
+    assert_eq!(a.profit(), Transfer::Credit(Money::from_amount(120)));
+    assert_eq!(ledger.account("b").unwrap().profit(), Transfer::Debit(Money::from_amount(120)));
+    assert_eq!(ledger.robots_by_profit(), vec!["a", "b"]);
+    assert_eq!(ledger.upgrade_returns(), vec![UpgradeReturn { item: "MINING_SPEED_1".to_string(), bought: 1, spent: Money::from_amount(30), earned_since: Money::from_amount(100) }]);
+  }
+
+  #[test]
+  fn test_running_totals() {
+    let mut ledger = Ledger::new();
+    ledger.record(entry(1, EntryKind::Purchase(ItemCategory::Robot), "ROBOT", 1, 100, None));
+    ledger.record(entry(1, EntryKind::Booking(Transfer::Debit(Money::from_amount(100))), "", 1, 100, None));
+    ledger.record(entry(2, EntryKind::Sale, "GEM", 4, 30, Some("a")));
+
+    let totals = ledger.totals();
+    assert_eq!(totals, LedgerTotals { income: Money::from_amount(120), spending: Money::from_amount(100), credited: Money::zero(), debited: Money::from_amount(100) });
+    assert_eq!(totals.to_string(), "income 120, spending 100, booked +0 -100");
+    assert_eq!(ledger.entries().len(), 3);
+  }
+}
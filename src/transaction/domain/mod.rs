@@ -0,0 +1,3 @@
+pub mod ledger;
+pub mod price_history;
+pub mod reconciliation;
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::domainprimitives::location::mineable_resource_type::MineableResourceType;
+use crate::domainprimitives::purchasing::money::Money;
+use crate::domainprimitives::purchasing::trade_item_type::TradeItemType;
+
+// prices remembered per resource and item
+const HISTORY_LENGTH: usize = 100;
+// rounds the trend is estimated from, older prices say little about where a price is heading
+const TREND_WINDOW: usize = 10;
+// a forecast never strays further than this share from the current price
+const MAX_FORECAST_CHANGE: f32 = 0.5;
+
+/// The prices of one resource or item, one per round, oldest first. Averages, trends and forecasts are estimates, so they come as floats.
+pub struct PriceSeries {
+  prices: Vec<(u16, Money)>, // (round, price)
+}
+
+impl PriceSeries {
+  fn new() -> Self {
+    Self {
+      prices: Vec::new(),
+    }
+  }
+
+  // a second price in the same round replaces the first
+  fn record(&mut self, round: u16, price: Money) {
+    if let Some(last) = self.prices.last_mut().filter(|(r, _)| *r == round) {
+      last.1 = price;
+      return;
+    }
+    self.prices.push((round, price));
+    if self.prices.len() > HISTORY_LENGTH {
+      self.prices.remove(0);
+    }
+  }
+
+  pub fn latest(&self) -> Option<Money> {
+    self.prices.last().map(|(_, price)| *price)
+  }
+
+  /// Average of the last `rounds` prices we got.
+  pub fn moving_average(&self, rounds: usize) -> Option<f32> {
+    let recent = &self.prices[self.prices.len().saturating_sub(rounds.max(1))..];
+    (!recent.is_empty()).then(|| recent.iter().map(|(_, price)| price.to_f32()).sum::<f32>() / recent.len() as f32)
+  }
+
+  /// Change of the price per round, the slope of a least squares line through the recent prices. 0 as long as we know only one.
+  pub fn trend(&self) -> f32 {
+    let recent = &self.prices[self.prices.len().saturating_sub(TREND_WINDOW)..];
+    if recent.len() < 2 {
+      return 0.;
+    }
+    let n = recent.len() as f32;
+    let mean_round = recent.iter().map(|(round, _)| *round as f32).sum::<f32>() / n;
+    let mean_price = recent.iter().map(|(_, price)| price.to_f32()).sum::<f32>() / n;
+    let covariance: f32 = recent.iter().map(|(round, price)| (*round as f32 - mean_round) * (price.to_f32() - mean_price)).sum();
+    let variance: f32 = recent.iter().map(|(round, _)| (*round as f32 - mean_round).powi(2)).sum();
+    if variance == 0. { 0. } else { covariance / variance }
+  }
+
+  /// The price `rounds` rounds from now if the trend goes on, within reason.
+  pub fn forecast(&self, current_price: f32, rounds: u32) -> f32 {
+    let forecast = current_price + self.trend() * rounds as f32;
+    forecast.clamp(current_price * (1. - MAX_FORECAST_CHANGE), current_price * (1. + MAX_FORECAST_CHANGE))
+  }
+}
+
+/// The prices of the resources and items over the last rounds, so a strategy can tell whether a price is climbing or falling.
+pub struct PriceHistory {
+  resources: HashMap<MineableResourceType, PriceSeries>,
+  items: HashMap<TradeItemType, PriceSeries>,
+  unknown: HashMap<String, PriceSeries>, // tradables the game service added that we don't know yet, by name
+}
+
+impl PriceHistory {
+  pub fn new() -> Self {
+    Self {
+      resources: HashMap::new(),
+      items: HashMap::new(),
+      unknown: HashMap::new(),
+    }
+  }
+
+  pub fn record_resource(&mut self, resource_type: MineableResourceType, round: u16, price: Money) {
+    self.resources.entry(resource_type).or_insert_with(PriceSeries::new).record(round, price);
+  }
+
+  pub fn record_item(&mut self, item: TradeItemType, round: u16, price: Money) {
+    self.items.entry(item).or_insert_with(PriceSeries::new).record(round, price);
+  }
+
+  /// Returns true the first time the tradable shows up.
+  pub fn record_unknown(&mut self, name: &str, round: u16, price: Money) -> bool {
+    let is_new = !self.unknown.contains_key(name);
+    self.unknown.entry(name.to_string()).or_insert_with(PriceSeries::new).record(round, price);
+    is_new
+  }
+
+  pub fn resource(&self, resource_type: MineableResourceType) -> Option<&PriceSeries> {
+    self.resources.get(&resource_type)
+  }
+
+  pub fn item(&self, item: TradeItemType) -> Option<&PriceSeries> {
+    self.items.get(&item)
+  }
+
+  /// The prices of the tradables we don't know, sorted by name.
+  pub fn unknown(&self) -> Vec<(&str, &PriceSeries)> {
+    let mut unknown: Vec<(&str, &PriceSeries)> = self.unknown.iter().map(|(name, series)| (name.as_str(), series)).collect();
+    unknown.sort_by(|a, b| a.0.cmp(b.0));
+    unknown
+  }
+
+  /// What the resource is expected to sell for `rounds` rounds from now, the current price without a history.
+  pub fn expected_resource_price(&self, resource_type: MineableResourceType, current_price: f32, rounds: u32) -> f32 {
+    self.resource(resource_type).map_or(current_price, |series| series.forecast(current_price, rounds))
+  }
+}
+
+impl fmt::Display for PriceSeries {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{} (average {:.2} over the last {} rounds, {:+.2}/round)",
+      self.latest().unwrap_or(Money::zero()),
+      self.moving_average(TREND_WINDOW).unwrap_or(0.),
+      TREND_WINDOW,
+      self.trend()
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_moving_average_and_trend() {
+    let mut history = PriceHistory::new();
+    for (round, price) in [(1, 10), (2, 12), (3, 13), (3, 14), (4, 16)] {
+      history.record_resource(MineableResourceType::PLATIN, round, Money::from_amount(price));
+    }
+    let platin = history.resource(MineableResourceType::PLATIN).unwrap();
+
+    assert_eq!(platin.latest(), Some(Money::from_amount(16)));
+    assert_eq!(platin.moving_average(2), Some(15.));
+    assert_eq!(platin.trend(), 2.);
+    assert_eq!(history.expected_resource_price(MineableResourceType::PLATIN, 16., 3), 22.);
+    // no wild guesses
+    assert_eq!(history.expected_resource_price(MineableResourceType::PLATIN, 16., 20), 24.);
+    assert_eq!(history.expected_resource_price(MineableResourceType::COAL, 5., 3), 5.);
+  }
+
+  #[test]
+  fn test_history_is_bounded() {
+    let mut history = PriceHistory::new();
+    for round in 0..150 {
+      history.record_item(TradeItemType::Robot, round, Money::from_amount(100));
+    }
+    let robot = history.item(TradeItemType::Robot).unwrap();
+
+    assert_eq!(robot.prices.len(), HISTORY_LENGTH);
+    assert_eq!(robot.prices[0].0, 50);
+    assert_eq!(robot.trend(), 0.);
+  }
+
+  #[test]
+  fn test_unknown_tradables_are_kept_by_name() {
+    let mut history = PriceHistory::new();
+    assert!(history.record_unknown("SHIELD_1", 1, Money::from_amount(80)));
+    assert!(!history.record_unknown("SHIELD_1", 2, Money::from_amount(90)));
+
+    let unknown = history.unknown();
+    assert_eq!(unknown.len(), 1);
+    assert_eq!((unknown[0].0, unknown[0].1.latest()), ("SHIELD_1", Some(Money::from_amount(90))));
+  }
+}
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::domainprimitives::purchasing::money::{Money, Transfer};
+use crate::domainprimitives::purchasing::trade_item_type::TradeItemType;
+
+// rounds the trading service gets to report a purchase, the events of a round may come in after the next one started
+const SETTLEMENT_ROUNDS: u16 = 1;
+// rounds a purchase that never happened is not tried again, doubled with every further failure
+const BLOCKED_ROUNDS: u16 = 2;
+const MAX_BLOCKED_ROUNDS: u16 = 32;
+
+/// A purchase a round planned and took off the local balance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectedPurchase {
+  pub item: TradeItemType,
+  pub robot_id: Option<String>, // None for robots
+  pub amount: u32,
+  pub unit_price: Money,
+}
+
+/// Where the trading service did something else than we planned.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Discrepancy {
+  Missing(ExpectedPurchase), // the amount that never arrived
+  PriceMismatch { item: TradeItemType, robot_id: Option<String>, expected: Money, charged: Money, correction: Option<Transfer> }, // None once a booking replaced the balance
+  Unexpected { item: String, robot_id: Option<String>, charged: Money },
+}
+
+/// The purchases a settled round never got and what they took off the local balance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settlement {
+  pub missing: Vec<Discrepancy>,
+  pub refund: Money, // only where no booking since replaced the balance with the bank's
+}
+
+struct OpenPurchase {
+  round: u16,
+  purchase: ExpectedPurchase,
+  booked: bool, // the bank booked a transaction in a later round than the purchase was planned
+}
+
+struct Failures {
+  count: u32,
+  blocked_until: u16,
+}
+
+/// Compares the purchases every round planned with what the trading service actually bought and charged,
+/// refunds the local balance for purchases that never happened and blocks them for a while, so we don't keep trying.
+pub struct Reconciliation {
+  open: Vec<OpenPurchase>,
+  failures: HashMap<(TradeItemType, Option<String>), Failures>,
+  discrepancies: u32,
+}
+
+impl Reconciliation {
+  pub fn new() -> Self {
+    Self {
+      open: Vec::new(),
+      failures: HashMap::new(),
+      discrepancies: 0,
+    }
+  }
+
+  // robots are bought for the player, everything else for a robot
+  fn key(item: TradeItemType, robot_id: Option<&str>) -> (TradeItemType, Option<String>) {
+    match item {
+      TradeItemType::Robot => (item, None),
+      _ => (item, robot_id.map(str::to_string)),
+    }
+  }
+
+  pub fn expect(&mut self, round: u16, purchases: Vec<ExpectedPurchase>) {
+    self.open.extend(purchases.into_iter().map(|purchase| OpenPurchase { round, purchase, booked: false }));
+  }
+
+  /// The bank sent a new balance in `round`, it already holds what the purchases planned before really cost.
+  /// Those planned in `round` may not be bought yet when the bank booked something else.
+  pub fn booked(&mut self, round: u16) {
+    for open in self.open.iter_mut().filter(|open| open.round < round) {
+      open.booked = true;
+    }
+  }
+
+  /// Matches a purchase of the trading service against the open ones, `item` is None for names we don't know.
+  pub fn bought(&mut self, item: Option<TradeItemType>, name: &str, robot_id: Option<&str>, amount: u32, total: Money) -> Option<Discrepancy> {
+    let key = item.map(|item| Self::key(item, robot_id));
+    let index = self.open.iter().position(|open| Some(Self::key(open.purchase.item, open.purchase.robot_id.as_deref())) == key);
+    let (Some(index), Some(key)) = (index, key) else {
+      self.discrepancies += 1;
+      return Some(Discrepancy::Unexpected { item: name.to_string(), robot_id: robot_id.map(str::to_string), charged: total });
+    };
+
+    self.failures.remove(&key);
+    let open = &mut self.open[index];
+    let expected = open.purchase.unit_price * amount as u64;
+    let booked = open.booked;
+    open.purchase.amount = open.purchase.amount.saturating_sub(amount);
+    if open.purchase.amount == 0 {
+      self.open.remove(index);
+    }
+    if expected == total {
+      return None;
+    }
+    self.discrepancies += 1;
+    // the local balance only took off what we expected to pay
+    let correction = (!booked).then(|| Transfer::between(expected, total));
+    Some(Discrepancy::PriceMismatch { item: key.0, robot_id: key.1, expected, charged: total, correction })
+  }
+
+  /// Closes the purchases planned `SETTLEMENT_ROUNDS` or more before `round`, whatever did not arrive by now never happened.
+  pub fn settle(&mut self, round: u16) -> Settlement {
+    let mut settlement = Settlement { missing: Vec::new(), refund: Money::zero() };
+    let (settled, open): (Vec<OpenPurchase>, Vec<OpenPurchase>) = std::mem::take(&mut self.open).into_iter().partition(|open| open.round + SETTLEMENT_ROUNDS <= round);
+    self.open = open;
+
+    for open in settled {
+      if !open.booked {
+        settlement.refund += open.purchase.unit_price * open.purchase.amount as u64;
+      }
+      let failures = self.failures.entry(Self::key(open.purchase.item, open.purchase.robot_id.as_deref())).or_insert(Failures { count: 0, blocked_until: 0 });
+      let blocked_rounds = BLOCKED_ROUNDS.saturating_mul(1 << failures.count.min(8)).min(MAX_BLOCKED_ROUNDS);
+      failures.count += 1;
+      failures.blocked_until = round + blocked_rounds;
+      self.discrepancies += 1;
+      settlement.missing.push(Discrepancy::Missing(open.purchase));
+    }
+    settlement
+  }
+
+  /// Whether the purchase failed lately and should not be tried again in `round`.
+  pub fn is_blocked(&self, item: TradeItemType, robot_id: Option<&str>, round: u16) -> bool {
+    self.failures.get(&Self::key(item, robot_id)).is_some_and(|failures| round < failures.blocked_until)
+  }
+
+  pub fn discrepancies(&self) -> u32 {
+    self.discrepancies
+  }
+}
+
+impl fmt::Display for Discrepancy {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Discrepancy::Missing(purchase) => write!(f, "{} {:?} for {:?} at {} never bought", purchase.amount, purchase.item, purchase.robot_id, purchase.unit_price),
+      Discrepancy::PriceMismatch { item, robot_id, expected, charged, .. } => write!(f, "{:?} for {:?} charged {} instead of {}", item, robot_id, charged, expected),
+      Discrepancy::Unexpected { item, robot_id, charged } => write!(f, "{} for {:?} charged {} without being planned", item, robot_id, charged),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn upgrade(robot_id: &str) -> ExpectedPurchase {
+    ExpectedPurchase { item: TradeItemType::Mining1, robot_id: Some(robot_id.to_string()), amount: 1, unit_price: Money::from_amount(50) }
+  }
+
+  #[test]
+  fn test_purchases_are_matched_against_the_plan() {
+    let mut reconciliation = Reconciliation::new();
+    let robots = ExpectedPurchase { item: TradeItemType::Robot, robot_id: None, amount: 2, unit_price: Money::from_amount(100) };
+    reconciliation.expect(3, vec![upgrade("a"), upgrade("b"), robots]);
+
+    assert_eq!(reconciliation.bought(Some(TradeItemType::Mining1), "MINING_1", Some("a"), 1, Money::from_amount(50)), None);
+    assert_eq!(reconciliation.bought(Some(TradeItemType::Robot), "ROBOT", Some("new"), 1, Money::from_amount(100)), None);
+    assert_eq!(
+      reconciliation.bought(Some(TradeItemType::Robot), "ROBOT", Some("newer"), 1, Money::from_amount(120)),
+      Some(Discrepancy::PriceMismatch {
+        item: TradeItemType::Robot,
+        robot_id: None,
+        expected: Money::from_amount(100),
+        charged: Money::from_amount(120),
+        correction: Some(Transfer::Debit(Money::from_amount(20))),
+      })
+    );
+    assert!(matches!(reconciliation.bought(Some(TradeItemType::Mining1), "MINING_1", Some("c"), 1, Money::from_amount(50)), Some(Discrepancy::Unexpected { .. })));
+    assert!(matches!(reconciliation.bought(None, "NEW_ITEM", None, 1, Money::from_amount(5)), Some(Discrepancy::Unexpected { .. })));
+
+    // too early to tell
+    assert_eq!(reconciliation.settle(3).missing, vec![]);
+    let settlement = reconciliation.settle(4);
+    assert_eq!(settlement.missing, vec![Discrepancy::Missing(upgrade("b"))]);
+    assert_eq!(settlement.refund, Money::from_amount(50));
+    assert_eq!(reconciliation.discrepancies(), 4);
+  }
+
+  #[test]
+  fn test_failed_purchases_are_blocked_for_longer_every_time() {
+    let mut reconciliation = Reconciliation::new();
+    reconciliation.expect(1, vec![upgrade("a")]);
+    reconciliation.booked(2);
+
+    // the bank's balance already tells what was charged
+    assert_eq!(reconciliation.settle(2).refund, Money::zero());
+    assert!(reconciliation.is_blocked(TradeItemType::Mining1, Some("a"), 3));
+    assert!(!reconciliation.is_blocked(TradeItemType::Mining1, Some("b"), 3));
+    assert!(!reconciliation.is_blocked(TradeItemType::Mining1, Some("a"), 4));
+
+    reconciliation.expect(4, vec![upgrade("a")]);
+    reconciliation.settle(5);
+    assert!(reconciliation.is_blocked(TradeItemType::Mining1, Some("a"), 8));
+    assert!(!reconciliation.is_blocked(TradeItemType::Mining1, Some("a"), 9));
+
+    // a purchase going through lifts the block
+    reconciliation.expect(9, vec![upgrade("a")]);
+    reconciliation.bought(Some(TradeItemType::Mining1), "MINING_1", Some("a"), 1, Money::from_amount(50));
+    assert!(!reconciliation.is_blocked(TradeItemType::Mining1, Some("a"), 9));
+  }
+
+  #[test]
+  fn test_a_booking_in_the_round_of_the_plan_does_not_cover_it() {
+    let mut reconciliation = Reconciliation::new();
+    reconciliation.expect(3, vec![upgrade("a")]);
+    // a sale is booked before the upgrade, which then fails
+    reconciliation.booked(3);
+
+    assert_eq!(reconciliation.settle(4).refund, Money::from_amount(50));
+  }
+
+  #[test]
+  fn test_price_mismatches_are_only_corrected_until_a_booking() {
+    let mut reconciliation = Reconciliation::new();
+    reconciliation.expect(3, vec![upgrade("a"), upgrade("b")]);
+    let correction = |discrepancy: Option<Discrepancy>| match discrepancy {
+      Some(Discrepancy::PriceMismatch { correction, .. }) => correction,
+      _ => panic!("no price mismatch"),
+    };
+
+    assert_eq!(correction(reconciliation.bought(Some(TradeItemType::Mining1), "MINING_1", Some("a"), 1, Money::from_amount(40))), Some(Transfer::Credit(Money::from_amount(10))));
+    reconciliation.booked(4);
+    assert_eq!(correction(reconciliation.bought(Some(TradeItemType::Mining1), "MINING_1", Some("b"), 1, Money::from_amount(60))), None);
+  }
+}